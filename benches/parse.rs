@@ -0,0 +1,66 @@
+//! Rough allocation/throughput benchmark for request head parsing.
+//!
+//! Run with `cargo bench --bench parse`. Not a statistical harness, but
+//! enough to spot order-of-magnitude regressions in the parse path.
+
+use std::time::Instant;
+
+use http_server_starter_rust::Request;
+
+const ITERATIONS: u32 = 200_000;
+
+fn main() {
+    // a realistic browser request: ~14 headers
+    let raw = b"GET /echo/hello-world HTTP/1.1\r\n\
+Host: localhost:4221\r\n\
+Connection: keep-alive\r\n\
+Cache-Control: max-age=0\r\n\
+Upgrade-Insecure-Requests: 1\r\n\
+User-Agent: Mozilla/5.0 (X11; Linux x86_64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0 Safari/537.36\r\n\
+Accept: text/html,application/xhtml+xml,application/xml;q=0.9,image/avif,image/webp,*/*;q=0.8\r\n\
+Accept-Encoding: gzip, deflate, br\r\n\
+Accept-Language: en-US,en;q=0.9\r\n\
+Sec-Fetch-Dest: document\r\n\
+Sec-Fetch-Mode: navigate\r\n\
+Sec-Fetch-Site: none\r\n\
+Sec-Fetch-User: ?1\r\n\
+Cookie: session=abc123; theme=dark\r\n\
+DNT: 1\r\n\
+\r\n";
+
+    // warmup
+    for _ in 0..10_000 {
+        std::hint::black_box(Request::from_utf8(std::hint::black_box(raw)).unwrap());
+    }
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(Request::from_utf8(std::hint::black_box(raw)).unwrap());
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "parse: {} iterations in {:?} ({} ns/iter)",
+        ITERATIONS,
+        elapsed,
+        elapsed.as_nanos() / ITERATIONS as u128
+    );
+
+    // parse plus the lookups a typical handler chain performs
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let req = Request::from_utf8(std::hint::black_box(raw)).unwrap();
+        std::hint::black_box(req.headers.get("Host"));
+        std::hint::black_box(req.headers.get("accept-encoding"));
+        std::hint::black_box(req.headers.get("Cookie"));
+        std::hint::black_box(req.headers.get("Content-Length"));
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "parse+lookup: {} iterations in {:?} ({} ns/iter)",
+        ITERATIONS,
+        elapsed,
+        elapsed.as_nanos() / ITERATIONS as u128
+    );
+}
@@ -0,0 +1,49 @@
+//! Allocation-churn benchmark for per-connection buffers.
+//!
+//! Run with `cargo bench --bench pool`. Compares a fresh 4 KB allocation
+//! plus first-touch write per "connection" against checking a buffer out
+//! of a reused slot, which is what the connection loop now does.
+
+use std::time::Instant;
+
+const ITERATIONS: u32 = 1_000_000;
+const BUF_SIZE: usize = 4096;
+
+fn touch(buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&[0x41; 512]);
+    std::hint::black_box(&buf);
+}
+
+fn main() {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let mut buf = Vec::with_capacity(BUF_SIZE);
+        touch(&mut buf);
+    }
+    let fresh = start.elapsed();
+
+    // the pool reduces to pop/push on a Mutex<Vec<_>> in the uncontended
+    // case, which is what a single accept loop sees
+    let pool = std::sync::Mutex::new(vec![Vec::with_capacity(BUF_SIZE)]);
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        let mut buf: Vec<u8> = pool.lock().unwrap().pop().unwrap();
+        touch(&mut buf);
+        buf.clear();
+        pool.lock().unwrap().push(buf);
+    }
+    let pooled = start.elapsed();
+
+    println!(
+        "fresh alloc: {} iterations in {:?} ({} ns/iter)",
+        ITERATIONS,
+        fresh,
+        fresh.as_nanos() / ITERATIONS as u128
+    );
+    println!(
+        "pooled:      {} iterations in {:?} ({} ns/iter)",
+        ITERATIONS,
+        pooled,
+        pooled.as_nanos() / ITERATIONS as u128
+    );
+}
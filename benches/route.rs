@@ -0,0 +1,46 @@
+//! Route matching benchmark at different table sizes.
+//!
+//! Run with `cargo bench --bench route`. With the compiled matcher,
+//! exact-match lookup cost should stay roughly flat as the route count
+//! grows from 100 to 1,000.
+
+use std::time::Instant;
+
+use http_server_starter_rust::{Request, Response, Router};
+
+const ITERATIONS: u32 = 200_000;
+
+fn handler(_req: &Request) -> Response {
+    Response::empty(200)
+}
+
+fn bench(table_size: u32) {
+    let mut router = Router::new("127.0.0.1:0");
+    for i in 0..table_size {
+        router.handle_func(&format!("/route/{i}"), handler, vec!["GET"]);
+    }
+    router.handle_func("/files/:?", handler, vec!["GET"]);
+
+    let matcher = router.compile_matcher();
+    // the worst case for a linear scan: the last registered exact route
+    let probe = format!("/route/{}", table_size - 1);
+
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        std::hint::black_box(matcher.match_path(std::hint::black_box(&probe)));
+    }
+    let elapsed = start.elapsed();
+
+    println!(
+        "match ({} routes): {} iterations in {:?} ({} ns/iter)",
+        table_size,
+        ITERATIONS,
+        elapsed,
+        elapsed.as_nanos() / ITERATIONS as u128
+    );
+}
+
+fn main() {
+    bench(100);
+    bench(1_000);
+}
@@ -0,0 +1,45 @@
+//! Rough throughput benchmark for response serialization.
+//!
+//! Run with `cargo bench --bench write`. Writes a 64 KB response into a
+//! reused buffer; useful for spotting regressions that reintroduce body
+//! copies on the write path.
+
+use std::time::Instant;
+
+use http_server_starter_rust::{Response, WriteContext};
+
+const ITERATIONS: u32 = 20_000;
+
+fn main() {
+    let rt = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+
+    let mut res = Response::new(200, "x".repeat(64 * 1024));
+    res.add_headers("Content-Type", "text/plain");
+
+    rt.block_on(async {
+        let mut out = Vec::with_capacity(70 * 1024);
+        let ctx = WriteContext::default();
+
+        // warmup
+        for _ in 0..1_000 {
+            out.clear();
+            std::hint::black_box(res.write_to(&mut out, &ctx).await.unwrap());
+        }
+
+        let start = Instant::now();
+        for _ in 0..ITERATIONS {
+            out.clear();
+            std::hint::black_box(res.write_to(&mut out, &ctx).await.unwrap());
+        }
+        let elapsed = start.elapsed();
+
+        println!(
+            "write: {} iterations in {:?} ({} ns/iter)",
+            ITERATIONS,
+            elapsed,
+            elapsed.as_nanos() / ITERATIONS as u128
+        );
+    });
+}
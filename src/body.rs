@@ -0,0 +1,188 @@
+//! Request body storage: in memory up to a threshold, spooled to a
+//! temp file beyond it.
+//!
+//! Handlers get a unified [`Body`] handle either way; the temp file
+//! backing a spooled body is deleted when the request is dropped. The
+//! threshold and spool directory are configured on the router, see
+//! [`crate::Router::spool_threshold`].
+
+use std::fmt::{self, Display};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+/// Default size past which bodies spool to disk.
+pub(crate) const DEFAULT_SPOOL_THRESHOLD: usize = 256 * 1024;
+
+#[derive(Debug, Default)]
+pub struct Body {
+    inner: Inner,
+}
+
+#[derive(Debug)]
+enum Inner {
+    Memory(Vec<u8>),
+    Spooled { path: PathBuf, len: u64 },
+}
+
+impl Default for Inner {
+    fn default() -> Inner {
+        Inner::Memory(Vec::new())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum BodyError {
+    /// The body was spooled to disk; use [`Body::reader`] instead.
+    Spooled,
+}
+
+impl Display for BodyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BodyError::Spooled => write!(f, "body is spooled to disk; use reader()"),
+        }
+    }
+}
+
+impl std::error::Error for BodyError {}
+
+impl Body {
+    pub(crate) fn memory(data: Vec<u8>) -> Body {
+        Body {
+            inner: Inner::Memory(data),
+        }
+    }
+
+    /// Writes `data` to a uniquely named temp file under `dir`.
+    pub(crate) fn spool(data: &[u8], dir: &Path) -> io::Result<Body> {
+        let name = format!(
+            "body-{}.tmp",
+            crate::crypto::base64url_encode(&crate::crypto::random_bytes(9))
+        );
+        let path = dir.join(name);
+        fs::write(&path, data)?;
+
+        Ok(Body {
+            inner: Inner::Spooled {
+                path,
+                len: data.len() as u64,
+            },
+        })
+    }
+
+    /// The body as a byte slice; errors when the body was spooled to
+    /// disk, in which case [`Body::reader`] streams it instead.
+    pub fn bytes(&self) -> Result<&[u8], BodyError> {
+        match &self.inner {
+            Inner::Memory(data) => Ok(data),
+            Inner::Spooled { .. } => Err(BodyError::Spooled),
+        }
+    }
+
+    /// A reader over the body, wherever it lives.
+    pub fn reader(&self) -> io::Result<Box<dyn Read + Send + '_>> {
+        match &self.inner {
+            Inner::Memory(data) => Ok(Box::new(data.as_slice())),
+            Inner::Spooled { path, .. } => Ok(Box::new(File::open(path)?)),
+        }
+    }
+
+    pub fn len(&self) -> u64 {
+        match &self.inner {
+            Inner::Memory(data) => data.len() as u64,
+            Inner::Spooled { len, .. } => *len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Drop for Body {
+    fn drop(&mut self) {
+        if let Inner::Spooled { path, .. } = &self.inner {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// Moves an over-threshold body out of `req.body` into a spooled
+/// [`Body`]; smaller bodies keep their in-memory handle.
+pub(crate) fn spool_if_large(req: &mut crate::Request, threshold: usize, dir: &Path) {
+    if req.body.len() <= threshold {
+        return;
+    }
+
+    match Body::spool(&req.body, dir) {
+        Ok(body) => {
+            req.raw_body = body;
+            req.body.clear();
+        }
+        // degraded but functional: the body just stays in memory
+        Err(e) => eprintln!("failed to spool request body: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn spool_path(body: &Body) -> PathBuf {
+        match &body.inner {
+            Inner::Spooled { path, .. } => path.clone(),
+            Inner::Memory(_) => panic!("body is not spooled"),
+        }
+    }
+
+    #[test]
+    fn small_bodies_stay_in_memory() {
+        let body = Body::memory(b"hello".to_vec());
+        assert_eq!(body.bytes().unwrap(), b"hello");
+        assert_eq!(body.len(), 5);
+
+        let mut read_back = String::new();
+        body.reader().unwrap().read_to_string(&mut read_back).unwrap();
+        assert_eq!(read_back, "hello");
+    }
+
+    #[test]
+    fn spooled_bodies_read_back_via_reader() {
+        let data = vec![b'x'; 10_000];
+        let body = Body::spool(&data, &std::env::temp_dir()).unwrap();
+
+        assert_eq!(body.bytes(), Err(BodyError::Spooled));
+        assert_eq!(body.len(), 10_000);
+
+        let mut read_back = Vec::new();
+        body.reader().unwrap().read_to_end(&mut read_back).unwrap();
+        assert_eq!(read_back, data);
+    }
+
+    #[test]
+    fn temp_file_removed_on_drop() {
+        let body = Body::spool(b"ephemeral", &std::env::temp_dir()).unwrap();
+        let path = spool_path(&body);
+        assert!(path.exists());
+
+        drop(body);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn over_threshold_requests_are_spooled() {
+        let mut req = crate::middleware::test_util::request("POST", "/upload");
+        req.body = vec![b'x'; 100];
+        req.raw_body = Body::memory(req.body.clone());
+
+        spool_if_large(&mut req, 1024, &std::env::temp_dir());
+        assert_eq!(req.body, vec![b'x'; 100], "small bodies are untouched");
+
+        spool_if_large(&mut req, 10, &std::env::temp_dir());
+        assert!(req.body.is_empty());
+        assert_eq!(req.raw_body.len(), 100);
+        assert_eq!(req.raw_body.bytes(), Err(BodyError::Spooled));
+    }
+}
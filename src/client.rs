@@ -0,0 +1,329 @@
+//! Minimal HTTP/1.1 client (`client` feature).
+//!
+//! Just enough to let integration tests and proxy-style handlers make
+//! outbound requests over plain TCP: `Content-Length` and chunked
+//! response bodies, an optional redirect limit, and a deadline. No TLS,
+//! no connection reuse.
+
+use std::fmt::{self, Display};
+use std::io;
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::{find_subslice, Headers};
+
+#[derive(Debug)]
+pub enum ClientError {
+    /// The URL could not be parsed; only `http://` is supported.
+    InvalidUrl(&'static str),
+    /// The response could not be parsed.
+    Malformed(&'static str),
+    /// The redirect limit was exceeded.
+    TooManyRedirects,
+    /// The request did not complete within the configured timeout.
+    Timeout,
+    Io(io::Error),
+}
+
+impl Display for ClientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClientError::InvalidUrl(reason) => write!(f, "invalid url: {}", reason),
+            ClientError::Malformed(reason) => write!(f, "malformed response: {}", reason),
+            ClientError::TooManyRedirects => write!(f, "too many redirects"),
+            ClientError::Timeout => write!(f, "request timed out"),
+            ClientError::Io(e) => write!(f, "io error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ClientError {}
+
+impl From<io::Error> for ClientError {
+    fn from(e: io::Error) -> ClientError {
+        ClientError::Io(e)
+    }
+}
+
+/// Knobs for [`request_with`]; [`request`] uses the defaults.
+pub struct Options {
+    /// Redirects to follow before giving up; 0 returns 3xx as-is.
+    pub max_redirects: usize,
+    /// Deadline for each request/response exchange.
+    pub timeout: Duration,
+}
+
+impl Default for Options {
+    fn default() -> Options {
+        Options {
+            max_redirects: 0,
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+pub struct ClientResponse {
+    pub status: u16,
+    pub headers: Headers,
+    pub body: Vec<u8>,
+}
+
+/// Performs a single HTTP request with default [`Options`].
+pub async fn request(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> Result<ClientResponse, ClientError> {
+    request_with(method, url, headers, body, &Options::default()).await
+}
+
+/// Performs an HTTP request, following up to `max_redirects` redirects.
+pub async fn request_with(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+    options: &Options,
+) -> Result<ClientResponse, ClientError> {
+    let mut url = url.to_owned();
+
+    for _ in 0..=options.max_redirects {
+        let exchange = exchange(method, &url, headers, body);
+        let res = match tokio::time::timeout(options.timeout, exchange).await {
+            Ok(res) => res?,
+            Err(_) => return Err(ClientError::Timeout),
+        };
+
+        if !matches!(res.status, 301 | 302 | 303 | 307 | 308) || options.max_redirects == 0 {
+            return Ok(res);
+        }
+        let location = match res.headers.get("Location") {
+            Some(location) => location,
+            None => return Ok(res),
+        };
+
+        // absolute URLs replace the target; anything else is treated
+        // as a path on the same host
+        url = if location.starts_with("http://") {
+            location.to_owned()
+        } else {
+            let (host, _) = split_url(&url)?;
+            format!("http://{}{}", host, location)
+        };
+    }
+
+    Err(ClientError::TooManyRedirects)
+}
+
+/// One request/response round trip over a fresh connection.
+async fn exchange(
+    method: &str,
+    url: &str,
+    headers: &[(&str, &str)],
+    body: &[u8],
+) -> Result<ClientResponse, ClientError> {
+    let (host, path) = split_url(url)?;
+
+    let mut head = format!("{} {} HTTP/1.1\r\nHost: {}\r\nConnection: close\r\n", method, path, host);
+    if !body.is_empty() {
+        head.push_str(&format!("Content-Length: {}\r\n", body.len()));
+    }
+    for (name, value) in headers {
+        head.push_str(&format!("{}: {}\r\n", name, value));
+    }
+    head.push_str("\r\n");
+
+    let addr = if host.contains(':') {
+        host.to_owned()
+    } else {
+        format!("{}:80", host)
+    };
+    let mut stream = TcpStream::connect(addr).await?;
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(body).await?;
+    stream.flush().await?;
+
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw).await?;
+    parse_response(&raw)
+}
+
+/// Splits `http://host[:port]/path?query` into (host, path).
+fn split_url(url: &str) -> Result<(&str, &str), ClientError> {
+    let rest = url
+        .strip_prefix("http://")
+        .ok_or(ClientError::InvalidUrl("only http:// urls are supported"))?;
+
+    match rest.split_once('/') {
+        Some(("", _)) => Err(ClientError::InvalidUrl("missing host")),
+        Some((host, _)) => Ok((host, &rest[host.len()..])),
+        None if rest.is_empty() => Err(ClientError::InvalidUrl("missing host")),
+        None => Ok((rest, "/")),
+    }
+}
+
+fn parse_response(raw: &[u8]) -> Result<ClientResponse, ClientError> {
+    let head_end =
+        find_subslice(raw, b"\r\n\r\n").ok_or(ClientError::Malformed("missing head boundary"))?;
+    let head = std::str::from_utf8(&raw[..head_end])
+        .map_err(|_| ClientError::Malformed("head is not utf-8"))?;
+    let rest = &raw[head_end + 4..];
+
+    let mut lines = head.split("\r\n");
+    let status_line = lines.next().ok_or(ClientError::Malformed("empty head"))?;
+    let status = status_line
+        .split(' ')
+        .nth(1)
+        .and_then(|code| code.parse().ok())
+        .ok_or(ClientError::Malformed("bad status line"))?;
+
+    let mut headers = Headers::new();
+    for line in lines {
+        if let Some((name, value)) = line.split_once(": ") {
+            headers.insert(name, value);
+        }
+    }
+
+    let body = if headers
+        .get("Transfer-Encoding")
+        .is_some_and(|te| te.eq_ignore_ascii_case("chunked"))
+    {
+        decode_chunked(rest)?
+    } else if let Some(len) = headers.get("Content-Length") {
+        let len: usize = len
+            .parse()
+            .map_err(|_| ClientError::Malformed("bad Content-Length"))?;
+        rest.get(..len)
+            .ok_or(ClientError::Malformed("body shorter than Content-Length"))?
+            .to_vec()
+    } else {
+        rest.to_vec()
+    };
+
+    Ok(ClientResponse {
+        status,
+        headers,
+        body,
+    })
+}
+
+/// Reassembles a chunked body; trailers are ignored.
+fn decode_chunked(mut rest: &[u8]) -> Result<Vec<u8>, ClientError> {
+    let mut body = Vec::new();
+
+    loop {
+        let line_end =
+            find_subslice(rest, b"\r\n").ok_or(ClientError::Malformed("missing chunk size"))?;
+        let size_line = std::str::from_utf8(&rest[..line_end])
+            .map_err(|_| ClientError::Malformed("bad chunk size"))?;
+        // chunk extensions after ';' are allowed but ignored
+        let size_hex = size_line.split(';').next().unwrap_or("").trim();
+        let size = usize::from_str_radix(size_hex, 16)
+            .map_err(|_| ClientError::Malformed("bad chunk size"))?;
+        rest = &rest[line_end + 2..];
+
+        if size == 0 {
+            return Ok(body);
+        }
+        let chunk = rest
+            .get(..size)
+            .ok_or(ClientError::Malformed("truncated chunk"))?;
+        body.extend_from_slice(chunk);
+        rest = rest
+            .get(size + 2..)
+            .ok_or(ClientError::Malformed("missing chunk terminator"))?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Request, Response, Router};
+    use pretty_assertions::assert_eq;
+
+    fn hello(_req: &Request) -> Response {
+        Response::new(200, "hello from the router")
+    }
+
+    fn moved(_req: &Request) -> Response {
+        Response::empty(302).add_header("Location", "/hello")
+    }
+
+    #[tokio::test]
+    async fn fetches_a_plain_response_from_the_router() {
+        let addr = "127.0.0.1:48253";
+        let mut r = Router::new(addr);
+        r.handle_func("/hello", hello, vec!["GET"]);
+        tokio::spawn(async move { r.serve().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let res = request("GET", &format!("http://{}/hello", addr), &[], b"")
+            .await
+            .unwrap();
+        assert_eq!(res.status, 200);
+        assert_eq!(res.headers.get("Content-Type"), Some("text/plain"));
+        assert_eq!(res.body, b"hello from the router");
+    }
+
+    #[tokio::test]
+    async fn follows_redirects_when_allowed() {
+        let addr = "127.0.0.1:48254";
+        let mut r = Router::new(addr);
+        r.handle_func("/hello", hello, vec!["GET"]);
+        r.handle_func("/old", moved, vec!["GET"]);
+        tokio::spawn(async move { r.serve().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let url = format!("http://{}/old", addr);
+        let res = request("GET", &url, &[], b"").await.unwrap();
+        assert_eq!(res.status, 302, "redirects are off by default");
+
+        let options = Options {
+            max_redirects: 3,
+            ..Options::default()
+        };
+        let res = request_with("GET", &url, &[], b"", &options).await.unwrap();
+        assert_eq!(res.status, 200);
+        assert_eq!(res.body, b"hello from the router");
+    }
+
+    #[tokio::test]
+    async fn reassembles_chunked_bodies() {
+        // the router cannot emit chunked responses yet, so serve a
+        // canned one from a raw listener
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            let _ = socket.read(&mut buf).await.unwrap();
+            socket
+                .write_all(
+                    b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n\
+                      5\r\nhello\r\n7\r\n, world\r\n0\r\n\r\n",
+                )
+                .await
+                .unwrap();
+        });
+
+        let res = request("GET", &format!("http://{}/", addr), &[], b"")
+            .await
+            .unwrap();
+        assert_eq!(res.status, 200);
+        assert_eq!(res.body, b"hello, world");
+    }
+
+    #[test]
+    fn url_splitting() {
+        assert_eq!(split_url("http://example.com").unwrap(), ("example.com", "/"));
+        assert_eq!(
+            split_url("http://example.com:8080/a/b?c=d").unwrap(),
+            ("example.com:8080", "/a/b?c=d")
+        );
+        assert!(split_url("https://example.com").is_err());
+        assert!(split_url("http://").is_err());
+    }
+}
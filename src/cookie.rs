@@ -0,0 +1,348 @@
+//! Cookie helpers, including HMAC-signed cookies for tamper protection
+//! without a server-side session store.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::time::SystemTime;
+
+use crate::{crypto, httpdate, Request, Response};
+
+/// `SameSite` attribute of a [`Cookie`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SameSite {
+    Strict,
+    Lax,
+    None,
+}
+
+impl SameSite {
+    fn as_str(&self) -> &'static str {
+        match self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        }
+    }
+}
+
+/// A `Set-Cookie` value under construction; see
+/// [`Response::set_cookie`].
+///
+/// Attributes chain builder-style and serialize in the conventional
+/// order: `name=value; Path=/; Max-Age=3600; Expires=...; HttpOnly;
+/// Secure; SameSite=Lax`.
+#[derive(Debug, Clone)]
+pub struct Cookie {
+    name: String,
+    value: String,
+    path: Option<String>,
+    max_age: Option<i64>,
+    expires: Option<SystemTime>,
+    http_only: bool,
+    secure: bool,
+    same_site: Option<SameSite>,
+}
+
+impl Cookie {
+    pub fn new(name: impl Into<String>, value: impl Into<String>) -> Cookie {
+        Cookie {
+            name: name.into(),
+            value: value.into(),
+            path: None,
+            max_age: None,
+            expires: None,
+            http_only: false,
+            secure: false,
+            same_site: None,
+        }
+    }
+
+    pub fn path(mut self, path: impl Into<String>) -> Cookie {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Lifetime in seconds; `0` or negative deletes the cookie.
+    pub fn max_age(mut self, seconds: i64) -> Cookie {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Absolute expiry, serialized in IMF-fixdate form; see
+    /// [`httpdate::fmt_http_date`].
+    pub fn expires(mut self, at: SystemTime) -> Cookie {
+        self.expires = Some(at);
+        self
+    }
+
+    pub fn http_only(mut self) -> Cookie {
+        self.http_only = true;
+        self
+    }
+
+    pub fn secure(mut self) -> Cookie {
+        self.secure = true;
+        self
+    }
+
+    pub fn same_site(mut self, same_site: SameSite) -> Cookie {
+        self.same_site = Some(same_site);
+        self
+    }
+}
+
+impl Display for Cookie {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+        if let Some(path) = &self.path {
+            write!(f, "; Path={}", path)?;
+        }
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age)?;
+        }
+        if let Some(expires) = self.expires {
+            write!(f, "; Expires={}", httpdate::fmt_http_date(expires))?;
+        }
+        if self.http_only {
+            f.write_str("; HttpOnly")?;
+        }
+        if self.secure {
+            f.write_str("; Secure")?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site.as_str())?;
+        }
+        Ok(())
+    }
+}
+
+/// Keys for signing and verifying cookies.
+///
+/// The first key signs new cookies; every key is accepted during
+/// verification, so keys can be rotated by prepending a fresh one and
+/// keeping the old ones around until existing cookies expire.
+pub struct SigningKeys {
+    keys: Vec<Vec<u8>>,
+}
+
+impl SigningKeys {
+    /// Returns new SigningKeys, signing with the first key
+    ///
+    /// # Panics
+    /// Panics if `keys` is empty.
+    pub fn new(keys: &[&[u8]]) -> SigningKeys {
+        assert!(!keys.is_empty(), "at least one signing key is required");
+        SigningKeys {
+            keys: keys.iter().map(|k| k.to_vec()).collect(),
+        }
+    }
+
+    /// Signs `value`, appending a base64url HMAC-SHA256 signature.
+    pub(crate) fn sign(&self, value: &str) -> String {
+        let mac = crypto::hmac_sha256(&self.keys[0], value.as_bytes());
+        format!("{}.{}", value, crypto::base64url_encode(&mac))
+    }
+
+    /// Verifies a signed value in constant time against every key,
+    /// returning the bare value on a match.
+    pub(crate) fn verify(&self, signed: &str) -> Option<String> {
+        let (value, sig) = signed.rsplit_once('.')?;
+        let sig = crypto::base64url_decode(sig)?;
+
+        for key in self.keys.iter() {
+            let expected = crypto::hmac_sha256(key, value.as_bytes());
+            if crypto::constant_time_eq(&sig, &expected) {
+                return Some(value.to_owned());
+            }
+        }
+        None
+    }
+}
+
+impl Request {
+    /// Value of the named cookie from the `Cookie` header, if present.
+    /// Values containing `=` survive intact; only the first `=` in a
+    /// pair separates name from value
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.headers.get_all("Cookie").find_map(|header| {
+            header.split("; ").find_map(|pair| {
+                let (n, value) = pair.split_once('=')?;
+                (n == name).then_some(value)
+            })
+        })
+    }
+
+    /// All cookies from the `Cookie` header as a name -> value map;
+    /// a repeated name keeps the first occurrence, matching
+    /// [`cookie`]
+    ///
+    /// [`cookie`]: Request::cookie
+    pub fn cookies(&self) -> HashMap<String, String> {
+        let mut cookies = HashMap::new();
+        for header in self.headers.get_all("Cookie") {
+            for pair in header.split("; ") {
+                if let Some((name, value)) = pair.split_once('=') {
+                    cookies
+                        .entry(name.to_owned())
+                        .or_insert_with(|| value.to_owned());
+                }
+            }
+        }
+        cookies
+    }
+
+    /// Returns the verified value of a signed cookie, or None when the
+    /// cookie is absent or its signature does not match any key
+    ///
+    /// # Examples
+    /// ```
+    /// use http_server_starter_rust::{Request, Response, SigningKeys};
+    ///
+    /// let keys = SigningKeys::new(&[b"current-key", b"old-key"]);
+    ///
+    /// fn test(req: &Request, keys: &SigningKeys) -> Response {
+    ///     match req.signed_cookie("session", keys) {
+    ///         Some(session) => Response::new(200, session),
+    ///         None => Response::new(401, "unauthorized"),
+    ///     }
+    /// }
+    /// ```
+    pub fn signed_cookie(&self, name: &str, keys: &SigningKeys) -> Option<String> {
+        keys.verify(self.cookie(name)?)
+    }
+}
+
+impl Response {
+    /// Adds a `Set-Cookie` header for `cookie`. Each call appends its
+    /// own header line, so several cookies may be set on one response
+    ///
+    /// # Examples
+    /// ```
+    /// use http_server_starter_rust::{Cookie, Request, Response, SameSite};
+    ///
+    /// fn test(_req: &Request) -> Response {
+    ///     Response::empty(204)
+    ///         .set_cookie(Cookie::new("theme", "dark").path("/").max_age(86400))
+    ///         .set_cookie(Cookie::new("session", "abc").http_only().same_site(SameSite::Lax))
+    /// }
+    /// ```
+    pub fn set_cookie(mut self, cookie: Cookie) -> Response {
+        self.headers.insert("Set-Cookie", cookie.to_string());
+        self
+    }
+
+    /// Sets a cookie whose value is signed with the first key in `keys`
+    ///
+    /// # Examples
+    /// ```
+    /// use http_server_starter_rust::{Request, Response, SigningKeys};
+    ///
+    /// let keys = SigningKeys::new(&[b"current-key"]);
+    ///
+    /// fn test(_req: &Request, keys: &SigningKeys) -> Response {
+    ///     Response::empty(200).set_signed_cookie("session", "user1", keys)
+    /// }
+    /// ```
+    pub fn set_signed_cookie(self, name: &str, value: &str, keys: &SigningKeys) -> Response {
+        self.set_cookie(Cookie::new(name, keys.sign(value)).path("/").http_only())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+
+    fn request_with_cookie(header: &str) -> Request {
+        let mut req = request("GET", "/");
+        req.headers.insert("Cookie".to_owned(), header.to_owned());
+        req
+    }
+
+    #[test]
+    fn cookie_serializes_its_attributes_in_order() {
+        assert_eq!(Cookie::new("theme", "dark").to_string(), "theme=dark");
+        assert_eq!(
+            Cookie::new("session", "abc")
+                .path("/")
+                .max_age(3600)
+                .http_only()
+                .secure()
+                .same_site(SameSite::Lax)
+                .to_string(),
+            "session=abc; Path=/; Max-Age=3600; HttpOnly; Secure; SameSite=Lax"
+        );
+        assert_eq!(
+            Cookie::new("t", "x")
+                .expires(std::time::SystemTime::UNIX_EPOCH)
+                .to_string(),
+            "t=x; Expires=Thu, 01 Jan 1970 00:00:00 GMT"
+        );
+    }
+
+    #[test]
+    fn request_cookies_split_pairs_and_keep_embedded_equals() {
+        let req = request_with_cookie("theme=dark; token=abc=def==; empty=");
+        assert_eq!(req.cookie("theme"), Some("dark"));
+        assert_eq!(req.cookie("token"), Some("abc=def=="));
+        assert_eq!(req.cookie("empty"), Some(""));
+        assert_eq!(req.cookie("missing"), None);
+
+        let cookies = req.cookies();
+        assert_eq!(cookies.len(), 3);
+        assert_eq!(cookies["token"], "abc=def==");
+
+        assert!(request("GET", "/").cookies().is_empty());
+    }
+
+    #[test]
+    fn set_cookie_appends_a_line_per_cookie() {
+        let res = Response::empty(204)
+            .set_cookie(Cookie::new("a", "1").path("/"))
+            .set_cookie(Cookie::new("b", "2").http_only());
+
+        let lines: Vec<&str> = res.headers.get_all("Set-Cookie").collect();
+        assert_eq!(lines, vec!["a=1; Path=/", "b=2; HttpOnly"]);
+    }
+
+    #[test]
+    fn round_trip() {
+        let keys = SigningKeys::new(&[b"key-1"]);
+        let res = Response::empty(200).set_signed_cookie("session", "user1", &keys);
+
+        let set_cookie = res.headers.get("Set-Cookie").unwrap();
+        let signed = set_cookie
+            .strip_prefix("session=")
+            .unwrap()
+            .split(';')
+            .next()
+            .unwrap();
+
+        let req = request_with_cookie(&format!("session={}", signed));
+        assert_eq!(req.signed_cookie("session", &keys).unwrap(), "user1");
+    }
+
+    #[test]
+    fn tampered_value_rejected() {
+        let keys = SigningKeys::new(&[b"key-1"]);
+        let signed = keys.sign("user1");
+        let tampered = signed.replacen("user1", "admin", 1);
+
+        let req = request_with_cookie(&format!("session={}", tampered));
+        assert_eq!(req.signed_cookie("session", &keys), None);
+    }
+
+    #[test]
+    fn rotated_key_still_verifies() {
+        let old = SigningKeys::new(&[b"old-key"]);
+        let signed = old.sign("user1");
+
+        let rotated = SigningKeys::new(&[b"new-key", b"old-key"]);
+        let req = request_with_cookie(&format!("session={}", signed));
+        assert_eq!(req.signed_cookie("session", &rotated).unwrap(), "user1");
+
+        let new_only = SigningKeys::new(&[b"new-key"]);
+        assert_eq!(req.signed_cookie("session", &new_only), None);
+    }
+}
@@ -0,0 +1,260 @@
+//! Content-Encoding handling for request bodies.
+
+#[cfg(feature = "compression")]
+use std::io::Read;
+
+/// Why a request body could not be decoded; maps to the response status
+/// the connection loop sends back.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum DecodeError {
+    /// The compressed stream is invalid (400); only the real decoders
+    /// can tell, so the variant exists only with them
+    #[cfg(feature = "compression")]
+    Corrupt,
+    /// Decompressed data exceeds the configured limit (413)
+    #[cfg(feature = "compression")]
+    TooLarge,
+    /// An encoding this server does not support (415)
+    Unsupported,
+}
+
+impl DecodeError {
+    pub(crate) fn status(&self) -> u16 {
+        match self {
+            #[cfg(feature = "compression")]
+            DecodeError::Corrupt => 400,
+            #[cfg(feature = "compression")]
+            DecodeError::TooLarge => 413,
+            DecodeError::Unsupported => 415,
+        }
+    }
+
+    pub(crate) fn message(&self) -> &'static str {
+        match self {
+            #[cfg(feature = "compression")]
+            DecodeError::Corrupt => "invalid compressed body",
+            #[cfg(feature = "compression")]
+            DecodeError::TooLarge => "body too large",
+            DecodeError::Unsupported => "unsupported content encoding",
+        }
+    }
+}
+
+/// Decompresses a gzip- or deflate-encoded request body in the raw
+/// request bytes, stripping the `Content-Encoding` header so handlers
+/// see plain content.
+///
+/// Returns `Ok(None)` when the request carries no content encoding,
+/// leaving the buffer untouched. The decompressed size is capped at
+/// `limit` to guard against zip bombs.
+pub(crate) fn decode_request(buf: &[u8], limit: usize) -> Result<Option<Vec<u8>>, DecodeError> {
+    let boundary = match find_boundary(buf) {
+        Some(b) => b,
+        None => return Ok(None),
+    };
+    let (head, body) = (&buf[..boundary], &buf[boundary + 4..]);
+
+    let mut encoding = None;
+    let mut encoding_line = None;
+    for line in split_lines(head) {
+        let line_start = line.as_ptr() as usize - head.as_ptr() as usize;
+        if let Some(value) = header_value(line, b"content-encoding") {
+            encoding = Some(value.trim().to_ascii_lowercase());
+            encoding_line = Some((line_start, line_start + line.len()));
+        }
+    }
+
+    let encoding = match encoding.as_deref() {
+        None | Some("identity") | Some("") => return Ok(None),
+        Some(encoding) => encoding.to_owned(),
+    };
+
+    let body = match encoding.as_str() {
+        "gzip" => decompress_gzip(body, limit)?,
+        "deflate" => decompress_deflate(body, limit)?,
+        _ => return Err(DecodeError::Unsupported),
+    };
+
+    let (line_start, line_end) = encoding_line.expect("encoding implies its header line");
+    let mut out = Vec::with_capacity(head.len() + body.len() + 4);
+    out.extend_from_slice(&head[..line_start]);
+    // skip the header line and its trailing CRLF
+    out.extend_from_slice(&head[(line_end + 2).min(head.len())..]);
+    out.extend_from_slice(b"\r\n\r\n");
+    out.extend_from_slice(&body);
+    Ok(Some(out))
+}
+
+#[cfg(feature = "compression")]
+fn decompress_gzip(body: &[u8], limit: usize) -> Result<Vec<u8>, DecodeError> {
+    decompress(flate2::read::GzDecoder::new(body), limit)
+}
+
+#[cfg(feature = "compression")]
+fn decompress_deflate(body: &[u8], limit: usize) -> Result<Vec<u8>, DecodeError> {
+    decompress(flate2::read::ZlibDecoder::new(body), limit)
+}
+
+#[cfg(feature = "compression")]
+fn decompress(reader: impl Read, limit: usize) -> Result<Vec<u8>, DecodeError> {
+    let mut out = Vec::new();
+    let mut reader = reader.take(limit as u64 + 1);
+    reader
+        .read_to_end(&mut out)
+        .map_err(|_| DecodeError::Corrupt)?;
+
+    if out.len() > limit {
+        return Err(DecodeError::TooLarge);
+    }
+    Ok(out)
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress_gzip(_body: &[u8], _limit: usize) -> Result<Vec<u8>, DecodeError> {
+    Err(DecodeError::Unsupported)
+}
+
+#[cfg(not(feature = "compression"))]
+fn decompress_deflate(_body: &[u8], _limit: usize) -> Result<Vec<u8>, DecodeError> {
+    Err(DecodeError::Unsupported)
+}
+
+/// Whether the request's `Accept-Encoding` headers offer gzip.
+///
+/// Handles comma-separated lists with optional quality parameters;
+/// unknown encodings are skipped and `gzip;q=0` is a refusal.
+pub(crate) fn accepts_gzip(headers: &crate::Headers) -> bool {
+    headers.get_all("Accept-Encoding").any(|value| {
+        value.split(',').any(|entry| {
+            let mut parts = entry.split(';');
+            if !parts.next().unwrap_or("").trim().eq_ignore_ascii_case("gzip") {
+                return false;
+            }
+            parts
+                .find_map(|p| p.trim().strip_prefix("q="))
+                .and_then(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0)
+                > 0.0
+        })
+    })
+}
+
+/// Gzips a response body, or `None` when compression support is
+/// compiled out (in which case the body goes out as-is).
+#[cfg(feature = "compression")]
+pub(crate) fn compress_response(body: &[u8]) -> Option<Vec<u8>> {
+    use std::io::Write;
+
+    let mut enc =
+        flate2::write::GzEncoder::new(Vec::with_capacity(body.len()), flate2::Compression::default());
+    enc.write_all(body).ok()?;
+    enc.finish().ok()
+}
+
+#[cfg(not(feature = "compression"))]
+pub(crate) fn compress_response(_body: &[u8]) -> Option<Vec<u8>> {
+    None
+}
+
+/// Index of the `\r\n\r\n` separating head from body.
+fn find_boundary(buf: &[u8]) -> Option<usize> {
+    buf.windows(4).position(|w| w == b"\r\n\r\n")
+}
+
+fn split_lines(head: &[u8]) -> impl Iterator<Item = &[u8]> {
+    head.split(|&b| b == b'\n')
+        .map(|line| line.strip_suffix(b"\r").unwrap_or(line))
+}
+
+/// Value of `line` if its header name matches `name` case-insensitively.
+fn header_value<'a>(line: &'a [u8], name: &[u8]) -> Option<&'a str> {
+    let colon = line.iter().position(|&b| b == b':')?;
+    if !line[..colon].eq_ignore_ascii_case(name) {
+        return None;
+    }
+    std::str::from_utf8(&line[colon + 1..]).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn request_bytes(encoding: Option<&str>, body: &[u8]) -> Vec<u8> {
+        let mut buf = b"POST /files/x HTTP/1.1\r\nHost: localhost\r\n".to_vec();
+        if let Some(encoding) = encoding {
+            buf.extend_from_slice(format!("Content-Encoding: {}\r\n", encoding).as_bytes());
+        }
+        buf.extend_from_slice(b"\r\n");
+        buf.extend_from_slice(body);
+        buf
+    }
+
+    #[test]
+    fn plain_request_untouched() {
+        let buf = request_bytes(None, b"hello");
+        assert_eq!(decode_request(&buf, 1024), Ok(None));
+    }
+
+    #[test]
+    fn unknown_encoding_unsupported() {
+        let buf = request_bytes(Some("br"), b"x");
+        assert_eq!(decode_request(&buf, 1024), Err(DecodeError::Unsupported));
+    }
+
+    #[test]
+    fn accept_encoding_negotiation() {
+        let accepts = |value: &str| {
+            let mut headers = crate::Headers::new();
+            headers.insert("Accept-Encoding", value);
+            accepts_gzip(&headers)
+        };
+
+        assert!(accepts("gzip"));
+        assert!(accepts("GZIP"));
+        assert!(accepts("deflate, gzip, br"));
+        assert!(accepts("br;q=1.0, gzip;q=0.5"));
+        assert!(!accepts("deflate, br"));
+        assert!(!accepts("gzip;q=0"), "q=0 refuses the encoding");
+        assert!(!accepts(""));
+    }
+
+    #[cfg(feature = "compression")]
+    mod gzip {
+        use super::*;
+        use pretty_assertions::assert_eq;
+
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        fn gzip(data: &[u8]) -> Vec<u8> {
+            let mut enc = GzEncoder::new(Vec::new(), Compression::default());
+            enc.write_all(data).unwrap();
+            enc.finish().unwrap()
+        }
+
+        #[test]
+        fn valid_gzip_body_decoded_and_header_stripped() {
+            let buf = request_bytes(Some("gzip"), &gzip(b"hello world"));
+            let decoded = decode_request(&buf, 1024).unwrap().unwrap();
+
+            let text = String::from_utf8(decoded).unwrap();
+            assert!(text.ends_with("\r\n\r\nhello world"));
+            assert!(!text.contains("Content-Encoding"));
+            assert!(text.contains("Host: localhost\r\n"));
+        }
+
+        #[test]
+        fn corrupt_stream_rejected() {
+            let buf = request_bytes(Some("gzip"), b"\x1f\x8bgarbage");
+            assert_eq!(decode_request(&buf, 1024), Err(DecodeError::Corrupt));
+        }
+
+        #[test]
+        fn bomb_exceeding_limit_rejected() {
+            let buf = request_bytes(Some("gzip"), &gzip(&vec![0u8; 100_000]));
+            assert_eq!(decode_request(&buf, 1000), Err(DecodeError::TooLarge));
+        }
+    }
+}
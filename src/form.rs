@@ -0,0 +1,166 @@
+//! Form body decoding (`application/x-www-form-urlencoded`).
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use crate::{Request, Response};
+
+/// Why a form body could not be decoded; `From<FormError>` renders the
+/// matching error response so handlers can bail with `err.into()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormError {
+    /// `Content-Type` is missing or not
+    /// `application/x-www-form-urlencoded` (415)
+    WrongContentType,
+    /// The body is not valid UTF-8 (400)
+    InvalidUtf8,
+    /// A percent escape is truncated or not hex (400)
+    InvalidEscape,
+}
+
+impl FormError {
+    /// Status code this error maps to.
+    pub fn status(&self) -> u16 {
+        match self {
+            FormError::WrongContentType => 415,
+            FormError::InvalidUtf8 | FormError::InvalidEscape => 400,
+        }
+    }
+}
+
+impl Display for FormError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormError::WrongContentType => {
+                write!(f, "expected application/x-www-form-urlencoded body")
+            }
+            FormError::InvalidUtf8 => write!(f, "form body is not valid utf-8"),
+            FormError::InvalidEscape => write!(f, "invalid percent escape in form body"),
+        }
+    }
+}
+
+impl std::error::Error for FormError {}
+
+impl From<FormError> for Response {
+    fn from(err: FormError) -> Response {
+        Response::new(err.status(), err)
+    }
+}
+
+impl Request {
+    /// Decodes an `application/x-www-form-urlencoded` body into a map
+    ///
+    /// `+` decodes as a space, percent escapes are decoded strictly,
+    /// and repeated keys keep the last value (matching [`parse_query`]).
+    /// An empty body with the right `Content-Type` is an empty map
+    ///
+    /// [`parse_query`]: crate::parse_query
+    ///
+    /// # Examples
+    /// ```
+    /// use http_server_starter_rust::{Request, Response};
+    ///
+    /// fn login(req: &Request) -> Response {
+    ///     let form = match req.form() {
+    ///         Ok(form) => form,
+    ///         Err(e) => return e.into(),
+    ///     };
+    ///     match form.get("user") {
+    ///         Some(user) => Response::new(200, format!("hello {}", user)),
+    ///         None => Response::new(400, "missing user"),
+    ///     }
+    /// }
+    /// ```
+    pub fn form(&self) -> Result<HashMap<String, String>, FormError> {
+        let mime = self
+            .headers
+            .get("Content-Type")
+            .map(|v| v.split(';').next().unwrap_or(v).trim())
+            .ok_or(FormError::WrongContentType)?;
+        if !mime.eq_ignore_ascii_case("application/x-www-form-urlencoded") {
+            return Err(FormError::WrongContentType);
+        }
+
+        let body = self.body_str().map_err(|_| FormError::InvalidUtf8)?;
+        let mut form = HashMap::new();
+        for pair in body.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            form.insert(decode(key)?, decode(value)?);
+        }
+        Ok(form)
+    }
+}
+
+/// Percent-decodes a form component, treating `+` as space; unlike the
+/// query-string path, malformed escapes are an error here rather than
+/// passed through.
+fn decode(s: &str) -> Result<String, FormError> {
+    crate::urlencoding::decode(&s.replace('+', " ")).ok_or(FormError::InvalidEscape)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+
+    fn form_request(content_type: Option<&str>, body: &[u8]) -> Request {
+        let mut req = request("POST", "/login");
+        if let Some(content_type) = content_type {
+            req.headers.insert("Content-Type", content_type);
+        }
+        req.body = body.to_vec();
+        req
+    }
+
+    #[test]
+    fn decodes_pairs_escapes_and_keeps_the_last_repeat() {
+        let req = form_request(
+            Some("application/x-www-form-urlencoded"),
+            b"user=two+words&note=a%2Fb&user=second&bare&=empty-key",
+        );
+
+        let form = req.form().unwrap();
+        assert_eq!(form["user"], "second");
+        assert_eq!(form["note"], "a/b");
+        assert_eq!(form["bare"], "");
+        assert_eq!(form[""], "empty-key");
+    }
+
+    #[test]
+    fn content_type_parameters_are_tolerated() {
+        let req = form_request(
+            Some("application/x-www-form-urlencoded; charset=UTF-8"),
+            b"a=1",
+        );
+        assert_eq!(req.form().unwrap()["a"], "1");
+    }
+
+    #[test]
+    fn empty_body_is_an_empty_map() {
+        let req = form_request(Some("application/x-www-form-urlencoded"), b"");
+        assert!(req.form().unwrap().is_empty());
+    }
+
+    #[test]
+    fn missing_or_wrong_content_type_is_415() {
+        let missing = form_request(None, b"a=1");
+        assert_eq!(missing.form(), Err(FormError::WrongContentType));
+
+        let json = form_request(Some("application/json"), b"{}");
+        let err = json.form().unwrap_err();
+        assert_eq!(err.status(), 415);
+        assert_eq!(Response::from(err).code(), 415);
+    }
+
+    #[test]
+    fn bad_escapes_and_bad_utf8_are_400() {
+        let escape = form_request(Some("application/x-www-form-urlencoded"), b"a=%zz");
+        assert_eq!(escape.form(), Err(FormError::InvalidEscape));
+        assert_eq!(FormError::InvalidEscape.status(), 400);
+
+        let utf8 = form_request(Some("application/x-www-form-urlencoded"), b"a=\xff\xfe");
+        assert_eq!(utf8.form(), Err(FormError::InvalidUtf8));
+    }
+}
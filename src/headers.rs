@@ -0,0 +1,190 @@
+//! Header storage tuned for the common case.
+//!
+//! Messages typically carry 8-20 headers, where a `HashMap` pays hashing
+//! plus two allocations per entry before a single lookup happens. Pairs
+//! are instead kept in an ordered `Vec` with linear case-insensitive
+//! lookup, which also preserves insertion order and duplicate fields;
+//! past [`LINEAR_LIMIT`] entries a lowercased index map is built so
+//! pathological requests do not degrade to O(n) per lookup.
+
+use std::collections::HashMap;
+
+/// Entry count past which an index map is maintained alongside the pairs.
+const LINEAR_LIMIT: usize = 32;
+
+/// Ordered, case-insensitive multimap of header names to values.
+#[derive(Debug, Default, Clone)]
+pub struct Headers {
+    pairs: Vec<(String, String)>,
+    /// lowercased name -> index of its first pair; only built once
+    /// `pairs` outgrows [`LINEAR_LIMIT`]
+    index: Option<HashMap<String, usize>>,
+}
+
+impl Headers {
+    pub fn new() -> Headers {
+        Headers::default()
+    }
+
+    /// Appends a header, keeping earlier fields with the same name.
+    pub fn insert(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+
+        if self.index.is_none() && self.pairs.len() >= LINEAR_LIMIT {
+            self.index = Some(
+                self.pairs
+                    .iter()
+                    .enumerate()
+                    .rev()
+                    .map(|(i, (name, _))| (name.to_ascii_lowercase(), i))
+                    .collect(),
+            );
+        }
+        if let Some(index) = &mut self.index {
+            index
+                .entry(name.to_ascii_lowercase())
+                .or_insert(self.pairs.len());
+        }
+
+        self.pairs.push((name, value.into()));
+    }
+
+    /// First value for `name`, compared case-insensitively.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        let i = match &self.index {
+            Some(index) => *index.get(&name.to_ascii_lowercase())?,
+            None => self
+                .pairs
+                .iter()
+                .position(|(n, _)| n.eq_ignore_ascii_case(name))?,
+        };
+        Some(&self.pairs[i].1)
+    }
+
+    /// All values for `name`, in insertion order.
+    pub fn get_all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.pairs
+            .iter()
+            .filter(move |(n, _)| n.eq_ignore_ascii_case(name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Whether any field named `name` is present.
+    pub fn contains_key(&self, name: &str) -> bool {
+        self.get(name).is_some()
+    }
+
+    /// Replaces every field named `name` (compared case-insensitively)
+    /// with a single pair, appended at the end.
+    pub fn set(&mut self, name: impl Into<String>, value: impl Into<String>) {
+        let name = name.into();
+        self.remove(&name);
+        self.insert(name, value);
+    }
+
+    /// Removes every field named `name`, compared case-insensitively.
+    pub fn remove(&mut self, name: &str) {
+        self.pairs.retain(|(n, _)| !n.eq_ignore_ascii_case(name));
+        // positions have shifted; the next insert past the limit
+        // rebuilds the index
+        self.index = None;
+    }
+
+    /// Keeps only the pairs for which `keep` returns true.
+    pub fn retain(&mut self, mut keep: impl FnMut(&str, &str) -> bool) {
+        self.pairs.retain(|(n, v)| keep(n, v));
+        self.index = None;
+    }
+
+    /// All (name, value) pairs in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.pairs.iter().map(|(n, v)| (n.as_str(), v.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+}
+
+impl std::ops::Index<&str> for Headers {
+    type Output = str;
+
+    /// First value for `name`, for terse test assertions.
+    ///
+    /// # Panics
+    /// Panics when no field named `name` is present; use [`get`] in
+    /// non-test code.
+    ///
+    /// [`get`]: Headers::get
+    fn index(&self, name: &str) -> &str {
+        match self.get(name) {
+            Some(value) => value,
+            None => panic!("no header named {:?}", name),
+        }
+    }
+}
+
+impl<K: Into<String>, V: Into<String>> FromIterator<(K, V)> for Headers {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Headers {
+        let mut headers = Headers::new();
+        for (name, value) in iter {
+            headers.insert(name, value);
+        }
+        headers
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn lookup_is_case_insensitive() {
+        let mut headers = Headers::new();
+        headers.insert("Content-Type", "text/plain");
+        assert_eq!(headers.get("content-type"), Some("text/plain"));
+        assert_eq!(headers.get("CONTENT-TYPE"), Some("text/plain"));
+        assert_eq!(headers.get("Content-Length"), None);
+    }
+
+    #[test]
+    fn insertion_order_and_duplicates_preserved() {
+        let mut headers = Headers::new();
+        headers.insert("Accept", "text/html");
+        headers.insert("Host", "localhost");
+        headers.insert("Accept", "text/plain");
+
+        assert_eq!(headers.get("Accept"), Some("text/html"));
+        assert_eq!(
+            headers.get_all("accept").collect::<Vec<_>>(),
+            vec!["text/html", "text/plain"]
+        );
+        assert_eq!(
+            headers.iter().map(|(n, _)| n).collect::<Vec<_>>(),
+            vec!["Accept", "Host", "Accept"]
+        );
+    }
+
+    #[test]
+    fn indexed_lookup_agrees_with_linear_scan() {
+        let mut headers = Headers::new();
+        for i in 0..(LINEAR_LIMIT * 2) {
+            headers.insert(format!("X-Header-{i}"), format!("{i}"));
+        }
+        headers.insert("X-Header-0", "duplicate");
+
+        assert!(headers.index.is_some(), "index should exist past the limit");
+        assert_eq!(headers.get("x-header-0"), Some("0"));
+        assert_eq!(headers.get(&format!("X-Header-{}", LINEAR_LIMIT)), Some(&*format!("{LINEAR_LIMIT}")));
+        assert_eq!(
+            headers.get_all("X-Header-0").collect::<Vec<_>>(),
+            vec!["0", "duplicate"]
+        );
+        assert_eq!(headers.len(), LINEAR_LIMIT * 2 + 1);
+    }
+}
@@ -0,0 +1,250 @@
+//! RFC 7231 HTTP date formatting and parsing.
+//!
+//! Implemented without external dependencies; used by anything emitting
+//! `Date`, `Last-Modified`, cookie `Expires` or similar headers.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+const DAYS: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+const DAYS_LONG: [&str; 7] = [
+    "Monday",
+    "Tuesday",
+    "Wednesday",
+    "Thursday",
+    "Friday",
+    "Saturday",
+    "Sunday",
+];
+pub(crate) const MONTHS: [&str; 12] = [
+    "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+];
+
+/// Formats a time as an IMF-fixdate, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+///
+/// Times before the UNIX epoch are clamped to the epoch, since HTTP has
+/// no use for earlier dates.
+///
+/// # Examples
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use http_server_starter_rust::httpdate::fmt_http_date;
+///
+/// let t = UNIX_EPOCH + Duration::from_secs(784111777);
+/// assert_eq!(fmt_http_date(t), "Sun, 06 Nov 1994 08:49:37 GMT");
+/// ```
+pub fn fmt_http_date(t: SystemTime) -> String {
+    let secs = t
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or(Duration::ZERO)
+        .as_secs();
+
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+    let (year, month, day) = civil_from_days(days);
+    let weekday = ((days % 7 + 7) + 3) % 7; // 1970-01-01 was a Thursday
+
+    format!(
+        "{}, {:02} {} {:04} {:02}:{:02}:{:02} GMT",
+        DAYS[weekday as usize],
+        day,
+        MONTHS[month as usize - 1],
+        year,
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60,
+    )
+}
+
+/// Parses an HTTP date in any of the three formats RFC 7231 requires
+/// receivers to accept: IMF-fixdate, RFC 850 and asctime.
+///
+/// Returns None for malformed input or dates before 1970.
+///
+/// # Examples
+/// ```
+/// use http_server_starter_rust::httpdate::{fmt_http_date, parse_http_date};
+///
+/// let t = parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT").unwrap();
+/// assert_eq!(fmt_http_date(t), "Sun, 06 Nov 1994 08:49:37 GMT");
+///
+/// // legacy formats parse to the same instant
+/// assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), Some(t));
+/// assert_eq!(parse_http_date("Sun Nov  6 08:49:37 1994"), Some(t));
+/// ```
+pub fn parse_http_date(s: &str) -> Option<SystemTime> {
+    let s = s.trim();
+    parse_imf_fixdate(s)
+        .or_else(|| parse_rfc850(s))
+        .or_else(|| parse_asctime(s))
+}
+
+/// `Sun, 06 Nov 1994 08:49:37 GMT`
+fn parse_imf_fixdate(s: &str) -> Option<SystemTime> {
+    let rest = s.split_once(", ").map(|(day, rest)| {
+        DAYS.contains(&day).then_some(rest)
+    })??;
+
+    let mut parts = rest.split_ascii_whitespace();
+    let day = parts.next()?.parse().ok()?;
+    let month = month_from_name(parts.next()?)?;
+    let year = parts.next()?.parse().ok()?;
+    let (h, m, sec) = parse_time(parts.next()?)?;
+    if parts.next()? != "GMT" {
+        return None;
+    }
+
+    timestamp(year, month, day, h, m, sec)
+}
+
+/// `Sunday, 06-Nov-94 08:49:37 GMT`
+fn parse_rfc850(s: &str) -> Option<SystemTime> {
+    let rest = s.split_once(", ").map(|(day, rest)| {
+        DAYS_LONG.contains(&day).then_some(rest)
+    })??;
+
+    let mut parts = rest.split_ascii_whitespace();
+    let mut date = parts.next()?.split('-');
+    let day = date.next()?.parse().ok()?;
+    let month = month_from_name(date.next()?)?;
+    let year: i64 = date.next()?.parse().ok()?;
+    // two-digit years: 69-99 are 19xx, 00-68 are 20xx
+    let year = if year < 69 { year + 2000 } else { year + 1900 };
+    let (h, m, sec) = parse_time(parts.next()?)?;
+    if parts.next()? != "GMT" {
+        return None;
+    }
+
+    timestamp(year, month, day, h, m, sec)
+}
+
+/// `Sun Nov  6 08:49:37 1994`
+fn parse_asctime(s: &str) -> Option<SystemTime> {
+    let mut parts = s.split_ascii_whitespace();
+    if !DAYS.contains(&parts.next()?) {
+        return None;
+    }
+    let month = month_from_name(parts.next()?)?;
+    let day = parts.next()?.parse().ok()?;
+    let (h, m, sec) = parse_time(parts.next()?)?;
+    let year = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+
+    timestamp(year, month, day, h, m, sec)
+}
+
+fn parse_time(s: &str) -> Option<(u64, u64, u64)> {
+    let mut parts = s.split(':');
+    let h = parts.next()?.parse().ok()?;
+    let m = parts.next()?.parse().ok()?;
+    let sec = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || h > 23 || m > 59 || sec > 60 {
+        return None;
+    }
+    Some((h, m, sec))
+}
+
+fn month_from_name(name: &str) -> Option<u32> {
+    MONTHS.iter().position(|m| *m == name).map(|i| i as u32 + 1)
+}
+
+fn timestamp(year: i64, month: u32, day: u32, h: u64, m: u64, s: u64) -> Option<SystemTime> {
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let days = days_from_civil(year, month, day);
+    if days < 0 {
+        return None; // before 1970
+    }
+
+    let secs = days as u64 * 86400 + h * 3600 + m * 60 + s;
+    Some(UNIX_EPOCH + Duration::from_secs(secs))
+}
+
+/// Days since 1970-01-01 for a civil date (Howard Hinnant's algorithm).
+fn days_from_civil(y: i64, m: u32, d: u32) -> i64 {
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = if y >= 0 { y } else { y - 399 } / 400;
+    let yoe = y - era * 400;
+    let mp = (m as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + d as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146097 + doe - 719468
+}
+
+/// Civil date for days since 1970-01-01 (inverse of [`days_from_civil`]).
+pub(crate) fn civil_from_days(z: i64) -> (i64, u32, u32) {
+    let z = z + 719468;
+    let era = if z >= 0 { z } else { z - 146096 } / 146097;
+    let doe = z - era * 146097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146096) / 365;
+    let y = yoe + era * 400;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let d = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let m = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    (if m <= 2 { y + 1 } else { y }, m, d)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const VECTORS: [(u64, &str); 4] = [
+        (0, "Thu, 01 Jan 1970 00:00:00 GMT"),
+        (784111777, "Sun, 06 Nov 1994 08:49:37 GMT"),
+        (1000000000, "Sun, 09 Sep 2001 01:46:40 GMT"),
+        (1709164800, "Thu, 29 Feb 2024 00:00:00 GMT"), // leap day
+    ];
+
+    #[test]
+    fn format_known_vectors() {
+        for (secs, text) in VECTORS {
+            assert_eq!(fmt_http_date(UNIX_EPOCH + Duration::from_secs(secs)), text);
+        }
+    }
+
+    #[test]
+    fn parse_format_round_trips() {
+        for (secs, text) in VECTORS {
+            let t = UNIX_EPOCH + Duration::from_secs(secs);
+            assert_eq!(parse_http_date(text), Some(t));
+            assert_eq!(parse_http_date(&fmt_http_date(t)), Some(t));
+        }
+    }
+
+    #[test]
+    fn legacy_formats_agree() {
+        let t = parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT");
+        assert!(t.is_some());
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), t);
+        assert_eq!(parse_http_date("Sun Nov  6 08:49:37 1994"), t);
+    }
+
+    #[test]
+    fn two_digit_year_windowing() {
+        let t = parse_http_date("Thursday, 01-Jan-04 00:00:00 GMT").unwrap();
+        assert_eq!(fmt_http_date(t), "Thu, 01 Jan 2004 00:00:00 GMT");
+    }
+
+    #[test]
+    fn pre_1970_dates_rejected() {
+        assert_eq!(parse_http_date("Wed, 01 Jan 1969 00:00:00 GMT"), None);
+    }
+
+    #[test]
+    fn pre_epoch_times_clamp_when_formatting() {
+        let t = UNIX_EPOCH - Duration::from_secs(86400);
+        assert_eq!(fmt_http_date(t), "Thu, 01 Jan 1970 00:00:00 GMT");
+    }
+
+    #[test]
+    fn garbage_rejected() {
+        assert_eq!(parse_http_date("not a date"), None);
+        assert_eq!(parse_http_date("Sun, 06 Nov 1994 08:49:37"), None);
+        assert_eq!(parse_http_date("Xxx, 06 Nov 1994 08:49:37 GMT"), None);
+    }
+}
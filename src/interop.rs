@@ -0,0 +1,231 @@
+//! Conversions to and from the [`http`] crate's message types.
+//!
+//! Enabled by the `http-interop` feature, for projects that already
+//! speak `http::Request`/`http::Response` with other libraries.
+//!
+//! What this crate does not model is dropped or defaulted in both
+//! directions: `http` extensions and versions other than HTTP/1.1 are
+//! discarded, [`Request::extensions`] and [`Request::remote_addr`]
+//! start out empty, and multi-valued response headers are joined with
+//! `", "` since [`Response`] stores one value per name. Bodies travel
+//! as raw bytes in both directions.
+
+use std::collections::HashMap;
+
+use crate::{normalize_path, parse_query, Body, Headers, Request, Response};
+
+/// A handler written against the `http` crate's types; see
+/// [`Router::handle_http_func`].
+///
+/// [`Router::handle_http_func`]: crate::Router::handle_http_func
+pub type HttpHandler = fn(http::Request<Vec<u8>>) -> http::Response<Vec<u8>>;
+
+impl TryFrom<http::Request<Vec<u8>>> for Request {
+    type Error = &'static str;
+
+    /// Fails when a header value is not UTF-8, which [`Request`]
+    /// cannot represent.
+    fn try_from(req: http::Request<Vec<u8>>) -> Result<Request, Self::Error> {
+        let (parts, body) = req.into_parts();
+
+        let raw_path = parts
+            .uri
+            .path_and_query()
+            .map(|pq| pq.as_str())
+            .unwrap_or("/")
+            .to_owned();
+
+        let mut headers = Headers::new();
+        for (name, value) in parts.headers.iter() {
+            let value = value.to_str().map_err(|_| "non-utf8 header value")?;
+            headers.insert(name.as_str(), value);
+        }
+
+        let raw_query = parts.uri.query().unwrap_or("").to_owned();
+
+        Ok(Request {
+            method: parts.method.as_str().parse().expect("infallible"),
+            version: crate::HttpVersion::default(),
+            path: normalize_path(&crate::split_target(parts.uri.path()).0, true),
+            raw_path,
+            query: parse_query(&raw_query),
+            raw_query,
+            headers,
+            raw_body: Body::memory(body.clone()),
+            body,
+            params: HashMap::new(),
+            extensions: HashMap::new(),
+            remote_addr: None,
+        })
+    }
+}
+
+impl TryFrom<&Request> for http::Request<Vec<u8>> {
+    type Error = &'static str;
+
+    /// Fails when the method, target, or a header does not survive the
+    /// `http` crate's stricter validation.
+    fn try_from(req: &Request) -> Result<http::Request<Vec<u8>>, Self::Error> {
+        let mut builder = http::Request::builder()
+            .method(req.method.as_str().as_bytes())
+            .uri(&req.raw_path);
+        for (key, val) in req.headers.iter() {
+            builder = builder.header(key, val);
+        }
+
+        builder
+            .body(req.body.clone())
+            .map_err(|_| "request not representable in http types")
+    }
+}
+
+impl TryFrom<Request> for http::Request<Vec<u8>> {
+    type Error = &'static str;
+
+    fn try_from(req: Request) -> Result<http::Request<Vec<u8>>, Self::Error> {
+        (&req).try_into()
+    }
+}
+
+impl From<Response> for http::Response<Vec<u8>> {
+    /// Infallible by construction: a status outside 100..=999 becomes
+    /// 500, and header names or values the `http` crate rejects are
+    /// dropped.
+    fn from(res: Response) -> http::Response<Vec<u8>> {
+        let body = match res.data {
+            Some(ref data) => data.to_bytes(),
+            None => vec![],
+        };
+
+        let mut out = http::Response::new(body);
+        *out.status_mut() = http::StatusCode::from_u16(res.code)
+            .unwrap_or(http::StatusCode::INTERNAL_SERVER_ERROR);
+        for (key, val) in res.headers.iter() {
+            if let (Ok(name), Ok(value)) = (
+                key.parse::<http::header::HeaderName>(),
+                val.parse::<http::header::HeaderValue>(),
+            ) {
+                out.headers_mut().append(name, value);
+            }
+        }
+        out
+    }
+}
+
+impl TryFrom<http::Response<Vec<u8>>> for Response {
+    type Error = &'static str;
+
+    /// Fails when a header value is not UTF-8. Repeated headers are
+    /// joined with `", "`.
+    fn try_from(res: http::Response<Vec<u8>>) -> Result<Response, Self::Error> {
+        let (parts, body) = res.into_parts();
+
+        let mut headers = crate::Headers::new();
+        for (name, value) in parts.headers.iter() {
+            let value = value.to_str().map_err(|_| "non-utf8 header value")?;
+            match headers.get(name.as_str()) {
+                Some(existing) => {
+                    let joined = format!("{}, {}", existing, value);
+                    headers.set(name.as_str(), joined);
+                }
+                None => headers.insert(name.as_str(), value),
+            }
+        }
+
+        let mut out = Response::empty(parts.status.as_u16());
+        out.headers = headers;
+        if !body.is_empty() {
+            out.data = Some(crate::ResponseData::Bytes(body));
+        }
+        Ok(out)
+    }
+}
+
+/// Bridges an [`HttpHandler`] into the router's own handler shape; see
+/// [`Router::handle_http_func`].
+///
+/// [`Router::handle_http_func`]: crate::Router::handle_http_func
+pub(crate) fn call(handler: HttpHandler, req: &Request) -> Response {
+    let http_req = match req.try_into() {
+        Ok(http_req) => http_req,
+        Err(err) => return Response::new(500, err),
+    };
+
+    match handler(http_req).try_into() {
+        Ok(res) => res,
+        Err(err) => Response::new(500, err),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Method;
+    use pretty_assertions::assert_eq;
+
+    fn http_request() -> http::Request<Vec<u8>> {
+        http::Request::builder()
+            .method("POST")
+            .uri("/a/./b?x=1")
+            .header("Accept", "text/plain")
+            .header("Accept", "text/html")
+            .header("Content-Length", "5")
+            .body(b"hello".to_vec())
+            .unwrap()
+    }
+
+    #[test]
+    fn http_request_round_trips() {
+        let req = Request::try_from(http_request()).unwrap();
+        assert_eq!(req.method, Method::Post);
+        assert_eq!(req.raw_path, "/a/./b?x=1");
+        assert_eq!(req.path, "/a/b");
+        assert_eq!(req.query["x"], "1");
+        assert_eq!(req.raw_query, "x=1");
+        assert_eq!(
+            req.headers.get_all("Accept").collect::<Vec<_>>(),
+            vec!["text/plain", "text/html"]
+        );
+        assert_eq!(req.body, b"hello");
+
+        let back = http::Request::try_from(&req).unwrap();
+        assert_eq!(back.method(), http::Method::POST);
+        assert_eq!(back.uri(), http_request().uri());
+        assert_eq!(
+            back.headers().get_all("Accept").iter().count(),
+            2,
+            "duplicate headers survive"
+        );
+        assert_eq!(back.body(), b"hello");
+    }
+
+    #[test]
+    fn response_round_trips() {
+        let res = Response::new(404, "missing").add_header("X-Trace", "abc");
+        let converted = http::Response::from(res);
+        assert_eq!(converted.status(), http::StatusCode::NOT_FOUND);
+        assert_eq!(converted.headers()["X-Trace"], "abc");
+        assert_eq!(converted.body(), b"missing");
+
+        let back = Response::try_from(converted).unwrap();
+        assert_eq!(back.code, 404);
+        assert_eq!(&back.headers["x-trace"], "abc");
+        assert_eq!(back.data.unwrap().text(), "missing");
+    }
+
+    #[test]
+    fn repeated_response_headers_join_and_bad_statuses_default() {
+        let mut res = http::Response::new(vec![]);
+        res.headers_mut()
+            .append("Vary", "Accept".parse().unwrap());
+        res.headers_mut()
+            .append("Vary", "Accept-Language".parse().unwrap());
+        let back = Response::try_from(res).unwrap();
+        assert_eq!(&back.headers["vary"], "Accept, Accept-Language");
+
+        let mut res = Response::new(200, "x");
+        res.code = 99;
+        let converted = http::Response::from(res);
+        assert_eq!(converted.status(), http::StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
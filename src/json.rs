@@ -0,0 +1,374 @@
+//! Minimal JSON parsing for crate-internal use (e.g. JWT claims), plus
+//! typed body extraction under the `json` feature.
+//!
+//! The internal parser only offers flat access: a top-level object is
+//! returned as a string map, with scalar values stringified and nested
+//! objects/arrays kept as their raw JSON text. [`Request::json`] and
+//! [`Response::json`] go through serde_json instead.
+//!
+//! [`Request::json`]: crate::Request::json
+//! [`Response::json`]: crate::Response::json
+
+use std::collections::HashMap;
+
+/// Parses a JSON object into a map of field name to value text.
+///
+/// Strings are unescaped; numbers, booleans and null keep their literal
+/// text; nested objects and arrays are returned as raw JSON.
+pub(crate) fn parse_object(input: &str) -> Option<HashMap<String, String>> {
+    let mut parser = Parser {
+        data: input.as_bytes(),
+        pos: 0,
+    };
+
+    parser.skip_ws();
+    let map = parser.parse_object()?;
+    parser.skip_ws();
+    if parser.pos != parser.data.len() {
+        return None;
+    }
+    Some(map)
+}
+
+struct Parser<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<u8> {
+        self.data.get(self.pos).copied()
+    }
+
+    fn skip_ws(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\r' | b'\n')) {
+            self.pos += 1;
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Option<()> {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            Some(())
+        } else {
+            None
+        }
+    }
+
+    fn parse_object(&mut self) -> Option<HashMap<String, String>> {
+        self.expect(b'{')?;
+        let mut map = HashMap::new();
+
+        self.skip_ws();
+        if self.peek() == Some(b'}') {
+            self.pos += 1;
+            return Some(map);
+        }
+
+        loop {
+            self.skip_ws();
+            let key = self.parse_string()?;
+            self.skip_ws();
+            self.expect(b':')?;
+            self.skip_ws();
+            let value = self.parse_value()?;
+            map.insert(key, value);
+
+            self.skip_ws();
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b'}') => {
+                    self.pos += 1;
+                    return Some(map);
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    fn parse_value(&mut self) -> Option<String> {
+        match self.peek()? {
+            b'"' => self.parse_string(),
+            b'{' | b'[' => self.parse_raw(),
+            _ => self.parse_literal(),
+        }
+    }
+
+    fn parse_string(&mut self) -> Option<String> {
+        self.expect(b'"')?;
+        let mut out = String::new();
+
+        loop {
+            match self.peek()? {
+                b'"' => {
+                    self.pos += 1;
+                    return Some(out);
+                }
+                b'\\' => {
+                    self.pos += 1;
+                    match self.peek()? {
+                        b'"' => out.push('"'),
+                        b'\\' => out.push('\\'),
+                        b'/' => out.push('/'),
+                        b'n' => out.push('\n'),
+                        b't' => out.push('\t'),
+                        b'r' => out.push('\r'),
+                        b'b' => out.push('\u{8}'),
+                        b'f' => out.push('\u{c}'),
+                        b'u' => {
+                            let hex = self.data.get(self.pos + 1..self.pos + 5)?;
+                            let code = u32::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?;
+                            out.push(char::from_u32(code)?);
+                            self.pos += 4;
+                        }
+                        _ => return None,
+                    }
+                    self.pos += 1;
+                }
+                _ => {
+                    let start = self.pos;
+                    while !matches!(self.peek()?, b'"' | b'\\') {
+                        self.pos += 1;
+                    }
+                    out.push_str(std::str::from_utf8(&self.data[start..self.pos]).ok()?);
+                }
+            }
+        }
+    }
+
+    /// Raw text of a nested object or array, brackets included.
+    fn parse_raw(&mut self) -> Option<String> {
+        let start = self.pos;
+        let mut depth = 0;
+
+        loop {
+            match self.peek()? {
+                b'{' | b'[' => {
+                    depth += 1;
+                    self.pos += 1;
+                }
+                b'}' | b']' => {
+                    depth -= 1;
+                    self.pos += 1;
+                    if depth == 0 {
+                        let raw = &self.data[start..self.pos];
+                        return Some(std::str::from_utf8(raw).ok()?.to_owned());
+                    }
+                }
+                b'"' => {
+                    self.parse_string()?;
+                }
+                _ => self.pos += 1,
+            }
+        }
+    }
+
+    fn parse_literal(&mut self) -> Option<String> {
+        let start = self.pos;
+        while matches!(
+            self.peek(),
+            Some(b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E' | b'a'..=b'z')
+        ) {
+            self.pos += 1;
+        }
+
+        if start == self.pos {
+            return None;
+        }
+        let text = std::str::from_utf8(&self.data[start..self.pos]).ok()?;
+        match text {
+            "true" | "false" | "null" => Some(text.to_owned()),
+            _ => {
+                text.parse::<f64>().ok()?;
+                Some(text.to_owned())
+            }
+        }
+    }
+}
+
+/// Why a JSON body could not be extracted (`json` feature);
+/// `From<JsonError>` renders the matching error response so handlers
+/// can bail with `err.into()`.
+#[cfg(feature = "json")]
+#[derive(Debug)]
+pub enum JsonError {
+    /// `Content-Type` is missing or not `application/json` (415)
+    WrongContentType,
+    /// The body is empty, malformed, or does not match the target
+    /// type (400)
+    Invalid(serde_json::Error),
+}
+
+#[cfg(feature = "json")]
+impl JsonError {
+    /// Status code this error maps to.
+    pub fn status(&self) -> u16 {
+        match self {
+            JsonError::WrongContentType => 415,
+            JsonError::Invalid(_) => 400,
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl std::fmt::Display for JsonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsonError::WrongContentType => write!(f, "expected application/json body"),
+            JsonError::Invalid(e) => write!(f, "invalid json body: {}", e),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl std::error::Error for JsonError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            JsonError::WrongContentType => None,
+            JsonError::Invalid(e) => Some(e),
+        }
+    }
+}
+
+#[cfg(feature = "json")]
+impl From<JsonError> for crate::Response {
+    fn from(err: JsonError) -> crate::Response {
+        crate::Response::new(err.status(), err)
+    }
+}
+
+#[cfg(feature = "json")]
+impl crate::Request {
+    /// Deserializes an `application/json` body into a typed value
+    /// (`json` feature)
+    ///
+    /// # Examples
+    /// ```
+    /// use http_server_starter_rust::{Request, Response};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct NewUser {
+    ///     name: String,
+    /// }
+    ///
+    /// fn create(req: &Request) -> Response {
+    ///     match req.json::<NewUser>() {
+    ///         Ok(user) => Response::new(201, user.name),
+    ///         Err(e) => e.into(),
+    ///     }
+    /// }
+    /// ```
+    pub fn json<T: serde::de::DeserializeOwned>(&self) -> Result<T, JsonError> {
+        let mime = self
+            .headers
+            .get("Content-Type")
+            .map(|v| v.split(';').next().unwrap_or(v).trim())
+            .ok_or(JsonError::WrongContentType)?;
+        if !mime.eq_ignore_ascii_case("application/json") {
+            return Err(JsonError::WrongContentType);
+        }
+
+        serde_json::from_slice(&self.body).map_err(JsonError::Invalid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn flat_object() {
+        let map = parse_object(r#"{"sub": "user1", "exp": 1700000000, "admin": true}"#).unwrap();
+        assert_eq!(map["sub"], "user1");
+        assert_eq!(map["exp"], "1700000000");
+        assert_eq!(map["admin"], "true");
+    }
+
+    #[test]
+    fn nested_values_kept_raw() {
+        let map = parse_object(r#"{"a": {"b": [1, "x"]}, "c": "d"}"#).unwrap();
+        assert_eq!(map["a"], r#"{"b": [1, "x"]}"#);
+        assert_eq!(map["c"], "d");
+    }
+
+    #[test]
+    fn escapes_unescaped() {
+        let map = parse_object(r#"{"msg": "a\"b\\c\ndA"}"#).unwrap();
+        assert_eq!(map["msg"], "a\"b\\c\ndA");
+    }
+
+    #[test]
+    fn malformed_rejected() {
+        assert!(parse_object("{").is_none());
+        assert!(parse_object(r#"{"a": }"#).is_none());
+        assert!(parse_object(r#"{"a": 1} trailing"#).is_none());
+        assert!(parse_object("[1, 2]").is_none());
+    }
+}
+
+#[cfg(all(test, feature = "json"))]
+mod typed_tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use crate::{Request, Response};
+    use pretty_assertions::assert_eq;
+
+    #[derive(serde::Deserialize, serde::Serialize, Debug, PartialEq)]
+    struct Event {
+        name: String,
+        seats: u32,
+    }
+
+    fn json_request(content_type: Option<&str>, body: &[u8]) -> Request {
+        let mut req = request("POST", "/events");
+        if let Some(content_type) = content_type {
+            req.headers.insert("Content-Type", content_type);
+        }
+        req.body = body.to_vec();
+        req
+    }
+
+    #[test]
+    fn typed_body_round_trips() {
+        let req = json_request(
+            Some("application/json; charset=utf-8"),
+            br#"{"name": "launch", "seats": 3}"#,
+        );
+
+        let event: Event = req.json().unwrap();
+        assert_eq!(event, Event { name: "launch".to_owned(), seats: 3 });
+    }
+
+    #[test]
+    fn missing_or_wrong_content_type_is_415() {
+        let missing = json_request(None, br#"{"name": "x", "seats": 1}"#);
+        let err = missing.json::<Event>().unwrap_err();
+        assert_eq!(err.status(), 415);
+
+        let form = json_request(Some("application/x-www-form-urlencoded"), b"name=x");
+        assert_eq!(Response::from(form.json::<Event>().unwrap_err()).code(), 415);
+    }
+
+    #[test]
+    fn empty_and_malformed_bodies_are_400() {
+        for body in [&b""[..], b"{\"name\": ", b"[1, 2]"] {
+            let req = json_request(Some("application/json"), body);
+            let err = req.json::<Event>().unwrap_err();
+            assert!(matches!(err, JsonError::Invalid(_)));
+            assert_eq!(err.status(), 400);
+        }
+    }
+
+    #[test]
+    fn response_json_serializes_and_labels_the_body() {
+        let res = Response::json(201, Event { name: "launch".to_owned(), seats: 3 });
+        assert_eq!(res.code(), 201);
+        assert_eq!(&res.headers["Content-Type"], "application/json");
+
+        let body = res.data.unwrap().to_bytes();
+        assert_eq!(&res.headers["Content-Length"], body.len().to_string());
+        assert_eq!(body, br#"{"name":"launch","seats":3}"#);
+    }
+}
@@ -0,0 +1,160 @@
+//! Accept-Language parsing and negotiation.
+//!
+//! Implements basic RFC 4647 lookup matching: exact tag first, then
+//! primary-subtag fallback (`en-GB` falls back to `en`).
+
+use std::cmp::Ordering;
+
+use crate::Request;
+
+/// One entry of an `Accept-Language` header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LanguageTag {
+    pub tag: String,
+    pub quality: f32,
+}
+
+impl Request {
+    /// Parses the `Accept-Language` header into tags ordered by
+    /// descending q-value; ties keep header order
+    ///
+    /// Entries with `q=0` (not acceptable) and malformed entries are
+    /// dropped
+    pub fn accept_languages(&self) -> Vec<LanguageTag> {
+        let header = match self.headers.get("Accept-Language") {
+            Some(header) => header,
+            None => return vec![],
+        };
+
+        let mut tags: Vec<LanguageTag> = header
+            .split(',')
+            .filter_map(|part| {
+                let part = part.trim();
+                let (tag, quality) = match part.split_once(';') {
+                    Some((tag, params)) => {
+                        let quality = params.trim().strip_prefix("q=")?.parse().ok()?;
+                        (tag.trim(), quality)
+                    }
+                    None => (part, 1.0),
+                };
+
+                (!tag.is_empty() && quality > 0.0).then(|| LanguageTag {
+                    tag: tag.to_owned(),
+                    quality,
+                })
+            })
+            .collect();
+
+        tags.sort_by(|a, b| {
+            b.quality
+                .partial_cmp(&a.quality)
+                .unwrap_or(Ordering::Equal)
+        });
+        tags
+    }
+
+    /// Picks the best of `available` for this request's
+    /// `Accept-Language`: exact tag match first, then primary-subtag
+    /// fallback; `*` matches whatever is listed first
+    ///
+    /// The result slots straight into the response:
+    ///
+    /// # Examples
+    /// ```
+    /// use http_server_starter_rust::{Request, Response};
+    ///
+    /// fn greet(req: &Request) -> Response {
+    ///     let lang = req.preferred_language(&["en", "fr"]).unwrap_or("en");
+    ///     Response::new(200, if lang == "fr" { "bonjour" } else { "hello" })
+    ///         .add_header("Content-Language", lang)
+    ///         .add_header("Vary", "Accept-Language")
+    /// }
+    /// ```
+    pub fn preferred_language<'a>(&self, available: &[&'a str]) -> Option<&'a str> {
+        for lang in self.accept_languages() {
+            if lang.tag == "*" {
+                return available.first().copied();
+            }
+
+            if let Some(hit) = available.iter().find(|a| a.eq_ignore_ascii_case(&lang.tag)) {
+                return Some(hit);
+            }
+
+            let primary = primary_subtag(&lang.tag);
+            if let Some(hit) = available
+                .iter()
+                .find(|a| primary_subtag(a).eq_ignore_ascii_case(primary))
+            {
+                return Some(hit);
+            }
+        }
+        None
+    }
+}
+
+fn primary_subtag(tag: &str) -> &str {
+    tag.split('-').next().unwrap_or(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+
+    fn with_header(value: &str) -> Request {
+        let mut req = request("GET", "/");
+        req.headers.insert("Accept-Language", value);
+        req
+    }
+
+    #[test]
+    fn orders_by_q_value() {
+        let req = with_header("en-US,en;q=0.9,fr;q=0.5");
+        let tags: Vec<String> = req
+            .accept_languages()
+            .into_iter()
+            .map(|t| t.tag)
+            .collect();
+        assert_eq!(tags, vec!["en-US", "en", "fr"]);
+    }
+
+    #[test]
+    fn exact_match_beats_primary_subtag_fallback() {
+        let req = with_header("en-US,en;q=0.9,fr;q=0.5");
+        assert_eq!(req.preferred_language(&["fr", "en-US"]), Some("en-US"));
+        // no exact en-US available: en-US falls back to its primary
+        assert_eq!(req.preferred_language(&["fr", "en"]), Some("en"));
+        assert_eq!(req.preferred_language(&["fr", "en-GB"]), Some("en-GB"));
+    }
+
+    #[test]
+    fn wildcard_matches_first_available() {
+        let req = with_header("de;q=0.8,*;q=0.5");
+        assert_eq!(req.preferred_language(&["ja", "fr"]), Some("ja"));
+    }
+
+    #[test]
+    fn no_overlap_yields_none() {
+        let req = with_header("de,nl;q=0.7");
+        assert_eq!(req.preferred_language(&["ja", "fr"]), None);
+    }
+
+    #[test]
+    fn unacceptable_and_malformed_entries_dropped() {
+        let req = with_header("fr;q=0,de;q=abc,en;q=0.3");
+        let tags: Vec<String> = req
+            .accept_languages()
+            .into_iter()
+            .map(|t| t.tag)
+            .collect();
+        assert_eq!(tags, vec!["en"]);
+    }
+
+    #[test]
+    fn missing_header_is_empty() {
+        let req = request("GET", "/");
+        assert_eq!(req.accept_languages(), vec![]);
+        assert_eq!(req.preferred_language(&["en"]), None);
+    }
+}
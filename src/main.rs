@@ -1,17 +1,27 @@
-use std::{collections::HashMap, env, fs};
+use std::{collections::HashMap, env, fs, path::Path};
 
-use http_server_starter_rust::{Request, Response, Router};
+use http_server_starter_rust::{Method, Request, Response, Router};
 
 #[tokio::main]
 async fn main() {
     let port = "127.0.0.1:4221";
     let mut r = Router::new(port);
 
+    // resolved once at startup; the handler captures it
+    let directory = env::current_dir()
+        .unwrap()
+        .join(env::args().nth(2).unwrap_or_default());
+
     r.handle_func("/", base_handler, vec!["GET"]);
     r.handle_func("/echo/:?", echo_handler, vec!["GET"]);
     r.handle_func("/user-agent", user_agent_handler, vec!["GET"]);
-    r.handle_func("/files/:?", files_handler, vec!["GET", "POST"]);
+    r.handle_func(
+        "/files/:?",
+        move |req| files_handler(req, &directory),
+        vec!["GET", "POST"],
+    );
     r.handle_func("/json", json_handler, vec!["GET"]);
+    r.handle_func("/echo-raw", upgrade_handler, vec!["GET"]);
 
     println!("Listening on port {}", port);
     if let Err(e) = r.serve().await {
@@ -26,6 +36,33 @@ fn json_handler(_req: &Request) -> Response {
     Response::json(200, data)
 }
 
+/// Leaves HTTP behind: after the 101 the connection is a raw byte
+/// echo, e.g. `nc 127.0.0.1 4221` then typing once the head arrives.
+fn upgrade_handler(_req: &Request) -> Response {
+    Response::empty(101)
+        .add_header("Upgrade", "echo")
+        .add_header("Connection", "Upgrade")
+        .upgrade(|mut stream, leftover| async move {
+            use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+            // bytes the server read past the request head come first
+            if stream.write_all(&leftover).await.is_err() {
+                return;
+            }
+            let mut buf = [0; 1024];
+            loop {
+                match stream.read(&mut buf).await {
+                    Ok(0) | Err(_) => return,
+                    Ok(n) => {
+                        if stream.write_all(&buf[..n]).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        })
+}
+
 fn base_handler(_req: &Request) -> Response {
     Response::empty(200)
 }
@@ -42,24 +79,17 @@ fn user_agent_handler(req: &Request) -> Response {
     Response::new(200, agent)
 }
 
-fn files_handler(req: &Request) -> Response {
+fn files_handler(req: &Request, directory: &Path) -> Response {
     let filename = req.path.strip_prefix("/files/").unwrap();
-    let args: Vec<String> = env::args().collect();
-    let directory = env::current_dir()
-        .unwrap()
-        .join(&args.get(2).expect("missing directory param"));
     let file_path = directory.join(filename);
-    let contents = fs::read_to_string(file_path.clone());
 
-    if req.method == "POST" {
-        fs::write(file_path, req.body.clone()).expect("unable to write");
+    if req.method == Method::Post {
+        fs::write(file_path, &req.body).expect("unable to write");
         return Response::empty(201);
     }
 
-    if let Err(e) = contents {
-        return Response::new(404, e);
+    match fs::read(file_path) {
+        Ok(contents) => Response::bytes(200, contents),
+        Err(e) => Response::new(404, e),
     }
-
-    let contents = contents.unwrap();
-    Response::new(200, contents).add_header("Content-Type", "application/octet-stream")
 }
@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::middleware::Middleware;
+use crate::{Request, Response};
+
+const MISS_KEY: &str = "cache.miss";
+
+/// Server-side caching of GET responses.
+///
+/// Caches successful GET responses keyed on method, path and the values
+/// of any configured vary headers, serving hits without invoking the
+/// handler and adding an `X-Cache: HIT` header. Responses carrying
+/// `Cache-Control: no-store` or `private` are never cached. Entries
+/// expire after the TTL, the least recently used entry is evicted past
+/// the entry budget, and any non-GET request to a path invalidates that
+/// path's entries.
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use http_server_starter_rust::{Router, middleware::Cache};
+///
+/// let mut r = Router::new("127.0.0.1:12345");
+/// r.use_middleware(Cache::new(Duration::from_secs(60), 1024).vary("Accept-Encoding"));
+/// ```
+pub struct Cache {
+    ttl: Duration,
+    max_entries: usize,
+    vary: Vec<String>,
+    entries: Mutex<HashMap<String, Entry>>,
+}
+
+struct Entry {
+    /// Request path without the query, so one non-GET invalidates
+    /// every query variant of the path at once
+    path: String,
+    code: u16,
+    headers: crate::Headers,
+    /// Raw body bytes; binary responses must replay exactly, and the
+    /// recorded `Content-Length` must keep framing them correctly
+    body: Option<Vec<u8>>,
+    expires: Instant,
+    last_used: Instant,
+}
+
+impl Cache {
+    /// Returns new Cache with the given TTL and entry budget.
+    pub fn new(ttl: Duration, max_entries: usize) -> Cache {
+        Cache {
+            ttl,
+            max_entries,
+            vary: vec![],
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Adds a request header whose value becomes part of the cache key.
+    pub fn vary(mut self, header: &str) -> Cache {
+        self.vary.push(header.to_owned());
+        self
+    }
+
+    fn key(&self, req: &Request) -> String {
+        let mut key = format!("{} {}", req.method, req.path);
+        if !req.raw_query.is_empty() {
+            key.push('?');
+            key.push_str(&req.raw_query);
+        }
+        for header in self.vary.iter() {
+            key.push('\n');
+            if let Some(val) = req.headers.get(header) {
+                key.push_str(val);
+            }
+        }
+        key
+    }
+}
+
+impl Middleware for Cache {
+    fn before(&self, req: &mut Request) -> Option<Response> {
+        if req.method != "GET" {
+            return None;
+        }
+
+        let key = self.key(req);
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.get_mut(&key) {
+            Some(entry) if entry.expires > Instant::now() => {
+                entry.last_used = Instant::now();
+                let mut res = Response::empty(entry.code);
+                res.headers = entry.headers.clone();
+                res.data = entry.body.clone().map(crate::ResponseData::Bytes);
+                Some(res.add_header("X-Cache", "HIT"))
+            }
+            _ => {
+                entries.remove(&key);
+                req.extensions.insert(MISS_KEY.to_owned(), key);
+                None
+            }
+        }
+    }
+
+    fn after(&self, req: &Request, res: Response) -> Response {
+        if req.method != "GET" {
+            let mut entries = self.entries.lock().unwrap();
+            entries.retain(|_, entry| entry.path != req.path);
+            return res;
+        }
+
+        let key = match req.extensions.get(MISS_KEY) {
+            Some(key) => key.clone(),
+            None => return res,
+        };
+
+        if res.code != 200 {
+            return res;
+        }
+        // a stream can only be read off once, on its way to this
+        // client; caching it would replay an empty body
+        if res.is_stream() {
+            return res;
+        }
+        if let Some(cc) = res.headers.get("Cache-Control") {
+            if cc.contains("no-store") || cc.contains("private") {
+                return res;
+            }
+        }
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            let lru = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.last_used)
+                .map(|(k, _)| k.clone());
+            if let Some(lru) = lru {
+                entries.remove(&lru);
+            }
+        }
+
+        entries.insert(
+            key,
+            Entry {
+                path: req.path.clone(),
+                code: res.code,
+                headers: res.headers.clone(),
+                body: res.data.as_ref().map(|d| d.to_bytes()),
+                expires: Instant::now() + self.ttl,
+                last_used: Instant::now(),
+            },
+        );
+
+        res
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+
+    fn run_miss(cache: &Cache, path: &str, body: &str) {
+        let mut req = request("GET", path);
+        assert!(cache.before(&mut req).is_none());
+        cache.after(&req, Response::new(200, body.to_owned()));
+    }
+
+    #[test]
+    fn hit_after_miss() {
+        let cache = Cache::new(Duration::from_secs(60), 16);
+        run_miss(&cache, "/slow", "expensive");
+
+        let mut req = request("GET", "/slow");
+        let res = cache.before(&mut req).expect("should be a hit");
+        assert_eq!(res.code, 200);
+        assert_eq!(res.headers.get("X-Cache").unwrap(), "HIT");
+        assert_eq!(res.data.unwrap().text(), "expensive");
+    }
+
+    #[test]
+    fn ttl_expiry() {
+        let cache = Cache::new(Duration::from_millis(10), 16);
+        run_miss(&cache, "/slow", "expensive");
+
+        std::thread::sleep(Duration::from_millis(20));
+        let mut req = request("GET", "/slow");
+        assert!(cache.before(&mut req).is_none());
+    }
+
+    #[test]
+    fn post_invalidates_path() {
+        let cache = Cache::new(Duration::from_secs(60), 16);
+        run_miss(&cache, "/slow", "expensive");
+
+        let post = request("POST", "/slow");
+        cache.after(&post, Response::empty(201));
+
+        let mut req = request("GET", "/slow");
+        assert!(cache.before(&mut req).is_none());
+    }
+
+    #[test]
+    fn no_store_not_cached() {
+        let cache = Cache::new(Duration::from_secs(60), 16);
+        let mut req = request("GET", "/private");
+        assert!(cache.before(&mut req).is_none());
+        cache.after(
+            &req,
+            Response::new(200, "secret").add_header("Cache-Control", "no-store"),
+        );
+
+        let mut req = request("GET", "/private");
+        assert!(cache.before(&mut req).is_none());
+    }
+
+    #[test]
+    fn binary_bodies_replay_byte_for_byte_and_streams_skip_the_cache() {
+        let cache = Cache::new(Duration::from_secs(60), 16);
+        let png = vec![0x89, b'P', b'N', b'G', 0x00, 0xff];
+
+        let mut req = request("GET", "/logo.png");
+        assert!(cache.before(&mut req).is_none());
+        cache.after(&req, Response::bytes(200, png.clone()));
+
+        // the recorded Content-Length must still frame the bytes
+        let mut req = request("GET", "/logo.png");
+        let res = cache.before(&mut req).expect("should be a hit");
+        assert_eq!(res.headers.get("Content-Length").unwrap(), "6");
+        assert_eq!(res.data.unwrap().to_bytes(), png);
+
+        // a stream can only be read once: served, never cached
+        let mut req = request("GET", "/live");
+        assert!(cache.before(&mut req).is_none());
+        cache.after(&req, Response::stream(200, std::io::Cursor::new(b"live".to_vec())));
+        let mut req = request("GET", "/live");
+        assert!(cache.before(&mut req).is_none());
+    }
+
+    #[test]
+    fn distinct_queries_get_distinct_entries() {
+        let cache = Cache::new(Duration::from_secs(60), 16);
+
+        // built through the real target parsing, so the query lands in
+        // `raw_query` rather than `path`
+        let mut req = Request::builder().get("/search?q=a").build();
+        assert!(cache.before(&mut req).is_none());
+        cache.after(&req, Response::new(200, "results for a"));
+
+        // same path, different query: a miss, not a's results
+        let mut req = Request::builder().get("/search?q=b").build();
+        assert!(cache.before(&mut req).is_none());
+
+        let mut req = Request::builder().get("/search?q=a").build();
+        let res = cache.before(&mut req).expect("should be a hit");
+        assert_eq!(res.data.unwrap().text(), "results for a");
+
+        // a non-GET to the path invalidates every query variant
+        let post = Request::builder().post("/search").build();
+        cache.after(&post, Response::empty(201));
+        let mut req = Request::builder().get("/search?q=a").build();
+        assert!(cache.before(&mut req).is_none());
+    }
+
+    #[test]
+    fn lru_eviction_respects_budget() {
+        let cache = Cache::new(Duration::from_secs(60), 2);
+        run_miss(&cache, "/a", "a");
+        run_miss(&cache, "/b", "b");
+
+        // touch /a so /b becomes least recently used
+        let mut req = request("GET", "/a");
+        assert!(cache.before(&mut req).is_some());
+
+        run_miss(&cache, "/c", "c");
+        assert_eq!(cache.entries.lock().unwrap().len(), 2);
+
+        let mut req = request("GET", "/b");
+        assert!(cache.before(&mut req).is_none());
+    }
+}
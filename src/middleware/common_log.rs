@@ -0,0 +1,242 @@
+//! NCSA Common Log Format access logging.
+
+use std::fs::{File, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::middleware::Middleware;
+use crate::{httpdate, Request, Response};
+
+/// Appends NCSA common log format lines to a file:
+///
+/// ```text
+/// 127.0.0.1 - - [06/Nov/1994:08:49:37 +0000] "GET /path HTTP/1.1" 200 42
+/// ```
+///
+/// Lines are flushed as they are written. With [`max_size`] set the file
+/// rotates (rename to `<path>.1`, reopen) once it would grow past the
+/// limit; [`reopen`] supports external logrotate-style rotation. The
+/// writer is a cheap clone-able handle and safe to share across
+/// connections.
+///
+/// [`max_size`]: CommonLogWriter::max_size
+/// [`reopen`]: CommonLogWriter::reopen
+///
+/// # Examples
+/// ```no_run
+/// use http_server_starter_rust::{Router, middleware::CommonLogWriter};
+///
+/// let mut r = Router::new("127.0.0.1:12345");
+/// r.use_middleware(CommonLogWriter::new("/var/log/access.log").unwrap());
+/// ```
+#[derive(Clone)]
+pub struct CommonLogWriter {
+    state: Arc<LogState>,
+}
+
+struct LogState {
+    path: PathBuf,
+    file: Mutex<Sink>,
+    max_size: Option<u64>,
+}
+
+struct Sink {
+    file: File,
+    written: u64,
+}
+
+impl CommonLogWriter {
+    /// Opens `path` for appending, creating it if needed.
+    pub fn new(path: impl AsRef<Path>) -> io::Result<CommonLogWriter> {
+        let path = path.as_ref().to_path_buf();
+        let file = open_append(&path)?;
+        let written = file.metadata()?.len();
+
+        Ok(CommonLogWriter {
+            state: Arc::new(LogState {
+                path,
+                file: Mutex::new(Sink { file, written }),
+                max_size: None,
+            }),
+        })
+    }
+
+    /// Rotates once the log would grow past `bytes`: the current file
+    /// is renamed to `<path>.1` and a fresh one opened.
+    pub fn max_size(mut self, bytes: u64) -> CommonLogWriter {
+        Arc::get_mut(&mut self.state)
+            .expect("configure CommonLogWriter before cloning or attaching")
+            .max_size = Some(bytes);
+        self
+    }
+
+    /// Reopens the log file, picking up an external rename (logrotate's
+    /// reopen-on-SIGHUP convention).
+    pub fn reopen(&self) -> io::Result<()> {
+        let mut sink = self.state.file.lock().unwrap();
+        sink.file.flush()?;
+        sink.file = open_append(&self.state.path)?;
+        sink.written = sink.file.metadata()?.len();
+        Ok(())
+    }
+
+    fn write_line(&self, line: &str) -> io::Result<()> {
+        let mut sink = self.state.file.lock().unwrap();
+
+        if let Some(max_size) = self.state.max_size {
+            if sink.written + line.len() as u64 > max_size && sink.written > 0 {
+                sink.file.flush()?;
+                std::fs::rename(&self.state.path, self.state.path.with_extension("1"))?;
+                sink.file = open_append(&self.state.path)?;
+                sink.written = 0;
+            }
+        }
+
+        sink.file.write_all(line.as_bytes())?;
+        sink.file.flush()?;
+        sink.written += line.len() as u64;
+        Ok(())
+    }
+}
+
+impl Middleware for CommonLogWriter {
+    fn after(&self, req: &Request, res: Response) -> Response {
+        let host = req
+            .remote_addr
+            .map(|a| a.ip().to_string())
+            .unwrap_or_else(|| "-".to_owned());
+        let bytes = match &res.data {
+            Some(data) => data.len().to_string(),
+            None => "-".to_owned(),
+        };
+
+        let line = format!(
+            "{} - - [{}] \"{} {} HTTP/1.1\" {} {}\n",
+            host,
+            clf_date(SystemTime::now()),
+            req.method,
+            req.path,
+            res.code,
+            bytes,
+        );
+        if let Err(e) = self.write_line(&line) {
+            eprintln!("failed to write access log: {}", e);
+        }
+
+        res
+    }
+}
+
+fn open_append(path: &Path) -> io::Result<File> {
+    OpenOptions::new().create(true).append(true).open(path)
+}
+
+/// `06/Nov/1994:08:49:37 +0000`
+fn clf_date(t: SystemTime) -> String {
+    let secs = t
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let (year, month, day) = httpdate::civil_from_days((secs / 86400) as i64);
+    let rem = secs % 86400;
+
+    format!(
+        "{:02}/{}/{:04}:{:02}:{:02}:{:02} +0000",
+        day,
+        httpdate::MONTHS[month as usize - 1],
+        year,
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+    use std::time::Duration;
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("clf-test-{}-{}", std::process::id(), name))
+    }
+
+    fn logged_request(log: &CommonLogWriter, path: &str, code: u16) {
+        let mut req = request("GET", path);
+        req.remote_addr = Some("10.1.2.3:5555".parse().unwrap());
+        log.after(&req, Response::new(code, "hello"));
+    }
+
+    #[test]
+    fn writes_common_log_format_lines() {
+        let path = temp_path("format.log");
+        let log = CommonLogWriter::new(&path).unwrap();
+
+        logged_request(&log, "/index.html", 200);
+        let contents = std::fs::read_to_string(&path).unwrap();
+        let line = contents.lines().next().unwrap();
+
+        assert!(
+            line.starts_with("10.1.2.3 - - ["),
+            "{}",
+            line
+        );
+        assert!(line.ends_with("\"GET /index.html HTTP/1.1\" 200 5"), "{}", line);
+
+        // date field: [dd/Mon/yyyy:hh:mm:ss +0000]
+        let date = line.split('[').nth(1).unwrap().split(']').next().unwrap();
+        assert!(date.ends_with(" +0000"), "{}", date);
+        let expected = clf_date(SystemTime::now());
+        assert_eq!(&date[..12], &expected[..12], "day/month/year agree");
+
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn rotates_when_size_exceeded() {
+        let path = temp_path("rotate.log");
+        let _ = std::fs::remove_file(&path);
+        let log = CommonLogWriter::new(&path).unwrap().max_size(80);
+
+        logged_request(&log, "/first", 200);
+        logged_request(&log, "/second", 404);
+
+        let rotated = std::fs::read_to_string(path.with_extension("1")).unwrap();
+        let current = std::fs::read_to_string(&path).unwrap();
+        assert!(rotated.contains("\"GET /first HTTP/1.1\" 200"), "{}", rotated);
+        assert!(current.contains("\"GET /second HTTP/1.1\" 404"), "{}", current);
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(path.with_extension("1")).unwrap();
+    }
+
+    #[test]
+    fn reopen_follows_an_external_rename() {
+        let path = temp_path("reopen.log");
+        let _ = std::fs::remove_file(&path);
+        let log = CommonLogWriter::new(&path).unwrap();
+
+        logged_request(&log, "/before", 200);
+        std::fs::rename(&path, path.with_extension("moved")).unwrap();
+        log.reopen().unwrap();
+        logged_request(&log, "/after", 200);
+
+        let moved = std::fs::read_to_string(path.with_extension("moved")).unwrap();
+        let current = std::fs::read_to_string(&path).unwrap();
+        assert!(moved.contains("/before"));
+        assert!(current.contains("/after"));
+        assert!(!current.contains("/before"));
+
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(path.with_extension("moved")).unwrap();
+    }
+
+    #[test]
+    fn date_formatting_is_stable() {
+        let t = UNIX_EPOCH + Duration::from_secs(784111777);
+        assert_eq!(clf_date(t), "06/Nov/1994:08:49:37 +0000");
+    }
+}
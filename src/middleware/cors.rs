@@ -0,0 +1,249 @@
+use crate::middleware::Middleware;
+use crate::{Method, Request, Response};
+
+/// Cross-origin resource sharing (CORS) headers for browser clients on
+/// another origin.
+///
+/// Preflight `OPTIONS` requests (those carrying an `Origin` and an
+/// `Access-Control-Request-Method`) are answered directly with a 204
+/// and the configured `Access-Control-Allow-*` headers; user handlers
+/// never see them. Normal responses to requests with an allowed
+/// `Origin` pick up `Access-Control-Allow-Origin`. Disallowed origins
+/// get no CORS headers at all — the browser enforces from there.
+///
+/// With [`credentials`] enabled the specific origin is always echoed,
+/// never `*`, as the fetch spec requires. Attach with
+/// [`Router::with_cors`] or, scoped, via [`Router::use_middleware`].
+///
+/// [`credentials`]: Cors::credentials
+/// [`Router::with_cors`]: crate::Router::with_cors
+/// [`Router::use_middleware`]: crate::Router::use_middleware
+///
+/// # Examples
+/// ```
+/// use http_server_starter_rust::{Router, middleware::Cors};
+///
+/// let mut r = Router::new("127.0.0.1:12345");
+/// r.with_cors(
+///     Cors::new()
+///         .origin("https://app.example.com")
+///         .headers(&["Content-Type", "Authorization"])
+///         .max_age(3600)
+///         .credentials(),
+/// );
+/// ```
+pub struct Cors {
+    any_origin: bool,
+    origins: Vec<String>,
+    methods: String,
+    headers: String,
+    max_age: Option<u64>,
+    credentials: bool,
+}
+
+impl Cors {
+    /// Returns new Cors allowing no origins; add them with [`origin`]
+    /// or [`any_origin`].
+    ///
+    /// [`origin`]: Cors::origin
+    /// [`any_origin`]: Cors::any_origin
+    pub fn new() -> Cors {
+        Cors {
+            any_origin: false,
+            origins: vec![],
+            methods: "GET, POST, PUT, DELETE, PATCH, OPTIONS".to_owned(),
+            headers: "Content-Type".to_owned(),
+            max_age: None,
+            credentials: false,
+        }
+    }
+
+    /// Allows every origin (`*`).
+    pub fn any_origin(mut self) -> Cors {
+        self.any_origin = true;
+        self
+    }
+
+    /// Adds an exact origin to the allow list, e.g.
+    /// `https://app.example.com`.
+    pub fn origin(mut self, origin: &str) -> Cors {
+        self.origins.push(origin.trim_end_matches('/').to_owned());
+        self
+    }
+
+    /// Replaces the methods advertised to preflights.
+    pub fn methods(mut self, methods: &[&str]) -> Cors {
+        self.methods = methods.join(", ");
+        self
+    }
+
+    /// Replaces the request headers advertised to preflights.
+    pub fn headers(mut self, headers: &[&str]) -> Cors {
+        self.headers = headers.join(", ");
+        self
+    }
+
+    /// Lets browsers cache preflight results for `seconds`.
+    pub fn max_age(mut self, seconds: u64) -> Cors {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Allows credentialed requests (cookies, HTTP auth). The allowed
+    /// origin is then echoed specifically, never `*`.
+    pub fn credentials(mut self) -> Cors {
+        self.credentials = true;
+        self
+    }
+
+    /// The `Access-Control-Allow-Origin` value for `origin`, or None
+    /// when the origin is not allowed.
+    fn allow_origin(&self, origin: &str) -> Option<String> {
+        if !self.any_origin && !self.origins.iter().any(|o| o == origin) {
+            return None;
+        }
+        if self.any_origin && !self.credentials {
+            Some("*".to_owned())
+        } else {
+            Some(origin.to_owned())
+        }
+    }
+
+    /// Stamps the origin-dependent headers onto `res`; a no-op for
+    /// disallowed origins.
+    fn apply(&self, origin: &str, res: Response) -> Response {
+        let allow = match self.allow_origin(origin) {
+            Some(allow) => allow,
+            None => return res,
+        };
+
+        let mut res = res.add_header("Access-Control-Allow-Origin", &allow);
+        if self.credentials {
+            res = res.add_header("Access-Control-Allow-Credentials", "true");
+        }
+        if allow != "*" {
+            // the response differs by requester; caches must not mix
+            res = res.add_header("Vary", "Origin");
+        }
+        res
+    }
+}
+
+impl Default for Cors {
+    fn default() -> Cors {
+        Cors::new()
+    }
+}
+
+impl Middleware for Cors {
+    fn before(&self, req: &mut Request) -> Option<Response> {
+        // only a preflight is intercepted; a plain OPTIONS (no
+        // requested method) or a disallowed origin falls through to
+        // normal handling
+        if req.method != Method::Options {
+            return None;
+        }
+        let origin = req.headers.get("Origin")?;
+        req.headers.get("Access-Control-Request-Method")?;
+        self.allow_origin(origin)?;
+
+        let mut res = Response::empty(204)
+            .add_header("Access-Control-Allow-Methods", &self.methods)
+            .add_header("Access-Control-Allow-Headers", &self.headers);
+        if let Some(seconds) = self.max_age {
+            res = res.add_header("Access-Control-Max-Age", &seconds.to_string());
+        }
+        Some(self.apply(origin, res))
+    }
+
+    fn after(&self, req: &Request, res: Response) -> Response {
+        match req.headers.get("Origin") {
+            Some(origin) => self.apply(origin, res),
+            None => res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+
+    fn preflight(origin: &str) -> Request {
+        let mut req = request("OPTIONS", "/api/data");
+        req.headers.insert("Origin", origin);
+        req.headers.insert("Access-Control-Request-Method", "POST");
+        req
+    }
+
+    #[test]
+    fn preflight_is_answered_without_reaching_handlers() {
+        let cors = Cors::new()
+            .origin("https://app.example.com")
+            .methods(&["GET", "POST"])
+            .max_age(600);
+
+        let res = cors
+            .before(&mut preflight("https://app.example.com"))
+            .expect("preflight short-circuits");
+        assert_eq!(res.code(), 204);
+        assert_eq!(
+            &res.headers["Access-Control-Allow-Origin"],
+            "https://app.example.com"
+        );
+        assert_eq!(&res.headers["Access-Control-Allow-Methods"], "GET, POST");
+        assert_eq!(&res.headers["Access-Control-Allow-Headers"], "Content-Type");
+        assert_eq!(&res.headers["Access-Control-Max-Age"], "600");
+    }
+
+    #[test]
+    fn plain_options_and_disallowed_preflights_fall_through() {
+        let cors = Cors::new().origin("https://app.example.com");
+
+        // no Access-Control-Request-Method: not a preflight
+        let mut req = request("OPTIONS", "/api/data");
+        req.headers.insert("Origin", "https://app.example.com");
+        assert!(cors.before(&mut req).is_none());
+
+        assert!(cors.before(&mut preflight("https://evil.example")).is_none());
+    }
+
+    #[test]
+    fn simple_requests_pick_up_the_allow_origin() {
+        let cors = Cors::new().origin("https://app.example.com");
+
+        let mut req = request("GET", "/api/data");
+        req.headers.insert("Origin", "https://app.example.com");
+        let res = cors.after(&req, Response::new(200, "data"));
+        assert_eq!(
+            &res.headers["Access-Control-Allow-Origin"],
+            "https://app.example.com"
+        );
+        assert_eq!(&res.headers["Vary"], "Origin");
+
+        // a disallowed origin gets no CORS headers, not an error
+        let mut req = request("GET", "/api/data");
+        req.headers.insert("Origin", "https://evil.example");
+        let res = cors.after(&req, Response::new(200, "data"));
+        assert_eq!(res.code(), 200);
+        assert!(!res.headers.contains_key("Access-Control-Allow-Origin"));
+    }
+
+    #[test]
+    fn credentials_echo_the_origin_instead_of_star() {
+        let open = Cors::new().any_origin();
+        let mut req = request("GET", "/api/data");
+        req.headers.insert("Origin", "https://app.example.com");
+        let res = open.after(&req, Response::empty(204));
+        assert_eq!(&res.headers["Access-Control-Allow-Origin"], "*");
+
+        let credentialed = Cors::new().any_origin().credentials();
+        let res = credentialed.after(&req, Response::empty(204));
+        assert_eq!(
+            &res.headers["Access-Control-Allow-Origin"],
+            "https://app.example.com"
+        );
+        assert_eq!(&res.headers["Access-Control-Allow-Credentials"], "true");
+    }
+}
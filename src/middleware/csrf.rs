@@ -0,0 +1,228 @@
+use std::collections::HashMap;
+
+use crate::crypto;
+use crate::middleware::Middleware;
+use crate::{Request, Response};
+
+const COOKIE_NAME: &str = "csrf_token";
+const HEADER_NAME: &str = "X-CSRF-Token";
+const FORM_FIELD: &str = "_csrf";
+
+/// Extension key under which the current token is exposed to handlers,
+/// for embedding in forms.
+pub const CSRF_TOKEN_KEY: &str = "csrf_token";
+
+const SET_COOKIE_KEY: &str = "csrf_token.issue";
+
+/// CSRF protection for cookie-authenticated form posts.
+///
+/// Issues a random token in a signed cookie and rejects state-changing
+/// methods (POST/PUT/PATCH/DELETE) with 403 unless the same token arrives
+/// in an `X-CSRF-Token` header or a `_csrf` form field. Safe methods pass
+/// through untouched and receive a token cookie when they lack one; the
+/// current token is exposed to handlers under the `csrf_token` extension.
+///
+/// # Examples
+/// ```
+/// use http_server_starter_rust::{Router, middleware::Csrf};
+///
+/// let mut r = Router::new("127.0.0.1:12345");
+/// r.use_middleware(Csrf::new(b"secret-key").exempt("/webhooks/"));
+/// ```
+pub struct Csrf {
+    key: Vec<u8>,
+    exempt: Vec<String>,
+}
+
+impl Csrf {
+    /// Returns new Csrf middleware signing tokens with `key`.
+    pub fn new(key: &[u8]) -> Csrf {
+        Csrf {
+            key: key.to_vec(),
+            exempt: vec![],
+        }
+    }
+
+    /// Excludes paths starting with `prefix` (e.g. webhook endpoints)
+    /// from token checks.
+    pub fn exempt(mut self, prefix: &str) -> Csrf {
+        self.exempt.push(prefix.to_owned());
+        self
+    }
+
+    fn sign(&self, token: &str) -> String {
+        let mac = crypto::hmac_sha256(&self.key, token.as_bytes());
+        format!("{}.{}", token, crypto::base64url_encode(&mac))
+    }
+
+    /// Verifies a signed cookie value, returning the bare token.
+    fn verify(&self, signed: &str) -> Option<String> {
+        let (token, sig) = signed.split_once('.')?;
+        let sig = crypto::base64url_decode(sig)?;
+        let expected = crypto::hmac_sha256(&self.key, token.as_bytes());
+        if crypto::constant_time_eq(&sig, &expected) {
+            Some(token.to_owned())
+        } else {
+            None
+        }
+    }
+
+    fn stored_token(&self, req: &Request) -> Option<String> {
+        let cookies = req.headers.get("Cookie")?;
+        let signed = cookies.split("; ").find_map(|c| {
+            let (name, value) = c.split_once('=')?;
+            (name == COOKIE_NAME).then_some(value)
+        })?;
+
+        self.verify(signed)
+    }
+
+    fn presented_token(&self, req: &Request) -> Option<String> {
+        if let Some(token) = req.headers.get(HEADER_NAME) {
+            return Some(token.to_owned());
+        }
+
+        let content_type = req.headers.get("Content-Type")?;
+        if !content_type.starts_with("application/x-www-form-urlencoded") {
+            return None;
+        }
+
+        parse_form(req.body_str().unwrap_or("")).remove(FORM_FIELD)
+    }
+}
+
+impl Middleware for Csrf {
+    fn before(&self, req: &mut Request) -> Option<Response> {
+        if self.exempt.iter().any(|p| req.path.starts_with(p)) {
+            return None;
+        }
+
+        let stored = self.stored_token(req);
+
+        if !matches!(req.method.as_str(), "POST" | "PUT" | "PATCH" | "DELETE") {
+            let token = match stored {
+                Some(token) => token,
+                None => {
+                    let token = crypto::base64url_encode(&crypto::random_bytes(32));
+                    req.extensions
+                        .insert(SET_COOKIE_KEY.to_owned(), self.sign(&token));
+                    token
+                }
+            };
+            req.extensions.insert(CSRF_TOKEN_KEY.to_owned(), token);
+            return None;
+        }
+
+        let stored = match stored {
+            Some(stored) => stored,
+            None => return Some(Response::new(403, "invalid csrf token")),
+        };
+        match self.presented_token(req) {
+            Some(ref presented)
+                if crypto::constant_time_eq(presented.as_bytes(), stored.as_bytes()) =>
+            {
+                req.extensions.insert(CSRF_TOKEN_KEY.to_owned(), stored);
+                None
+            }
+            _ => Some(Response::new(403, "invalid csrf token")),
+        }
+    }
+
+    fn after(&self, req: &Request, res: Response) -> Response {
+        match req.extensions.get(SET_COOKIE_KEY) {
+            Some(signed) => res.add_header(
+                "Set-Cookie",
+                &format!("{}={}; Path=/; HttpOnly", COOKIE_NAME, signed),
+            ),
+            None => res,
+        }
+    }
+}
+
+fn parse_form(body: &str) -> HashMap<String, String> {
+    body.split('&')
+        .filter_map(|pair| {
+            let (k, v) = pair.split_once('=')?;
+            Some((k.to_owned(), v.to_owned()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn request(method: &str, headers: Vec<(&str, &str)>, body: &str) -> Request {
+        let mut req = crate::middleware::test_util::request(method, "/submit");
+        req.headers = headers
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v.to_owned()))
+            .collect();
+        req.body = body.as_bytes().to_vec();
+        req
+    }
+
+    fn csrf() -> Csrf {
+        Csrf::new(b"test-key")
+    }
+
+    #[test]
+    fn missing_token_rejected() {
+        let mut req = request("POST", vec![], "");
+        let res = csrf().before(&mut req).expect("should short-circuit");
+        assert_eq!(res.code, 403);
+    }
+
+    #[test]
+    fn wrong_token_rejected() {
+        let csrf = csrf();
+        let cookie = format!("{}={}", COOKIE_NAME, csrf.sign("good-token"));
+        let mut req = request(
+            "POST",
+            vec![("Cookie", &cookie), (HEADER_NAME, "bad-token")],
+            "",
+        );
+        let res = csrf.before(&mut req).expect("should short-circuit");
+        assert_eq!(res.code, 403);
+    }
+
+    #[test]
+    fn correct_token_via_header_accepted() {
+        let csrf = csrf();
+        let cookie = format!("{}={}", COOKIE_NAME, csrf.sign("good-token"));
+        let mut req = request(
+            "POST",
+            vec![("Cookie", &cookie), (HEADER_NAME, "good-token")],
+            "",
+        );
+        assert!(csrf.before(&mut req).is_none());
+    }
+
+    #[test]
+    fn correct_token_via_form_field_accepted() {
+        let csrf = csrf();
+        let cookie = format!("{}={}", COOKIE_NAME, csrf.sign("good-token"));
+        let mut req = request(
+            "POST",
+            vec![
+                ("Cookie", &cookie),
+                ("Content-Type", "application/x-www-form-urlencoded"),
+            ],
+            "_csrf=good-token&name=x",
+        );
+        assert!(csrf.before(&mut req).is_none());
+    }
+
+    #[test]
+    fn safe_method_passes_and_issues_token() {
+        let mut req = request("GET", vec![], "");
+        let csrf = csrf();
+        assert!(csrf.before(&mut req).is_none());
+
+        let token = req.extensions.get(CSRF_TOKEN_KEY).unwrap().clone();
+        let res = csrf.after(&req, Response::empty(200));
+        let cookie = res.headers.get("Set-Cookie").unwrap();
+        assert!(cookie.contains(&token));
+    }
+}
@@ -0,0 +1,170 @@
+use crate::middleware::Middleware;
+use crate::{Request, Response};
+
+/// Extension key a TLS listener can set to mark a connection as secure.
+pub const SCHEME_KEY: &str = "scheme";
+
+/// Redirects plain-HTTP requests to the HTTPS origin.
+///
+/// Useful when running a TLS listener behind the same hostname as a plain
+/// one, or behind a TLS-terminating proxy. Requests that are not already
+/// effectively HTTPS (the connection's `scheme` extension or an
+/// `X-Forwarded-Proto: https` header) get a 301 to
+/// `https://<host><path><query>`, preserving the request target exactly.
+/// The Host header is used, falling back to a configured canonical host.
+///
+/// # Examples
+/// ```
+/// use http_server_starter_rust::{Router, middleware::HttpsRedirect};
+///
+/// let mut r = Router::new("127.0.0.1:12345");
+/// r.use_middleware(HttpsRedirect::new().canonical_host("example.com").hsts(31536000));
+/// ```
+pub struct HttpsRedirect {
+    canonical_host: Option<String>,
+    hsts_max_age: Option<u64>,
+}
+
+impl HttpsRedirect {
+    /// Returns new HttpsRedirect middleware.
+    pub fn new() -> HttpsRedirect {
+        HttpsRedirect {
+            canonical_host: None,
+            hsts_max_age: None,
+        }
+    }
+
+    /// Sets the host to redirect to when the request has no Host header.
+    pub fn canonical_host(mut self, host: &str) -> HttpsRedirect {
+        self.canonical_host = Some(host.to_owned());
+        self
+    }
+
+    /// Adds a `Strict-Transport-Security` header with the given max-age
+    /// (in seconds) to responses served over HTTPS.
+    pub fn hsts(mut self, max_age: u64) -> HttpsRedirect {
+        self.hsts_max_age = Some(max_age);
+        self
+    }
+
+    /// Whether the request is already effectively HTTPS; never redirect
+    /// these or clients would loop.
+    fn is_https(&self, req: &Request) -> bool {
+        if req.extensions.get(SCHEME_KEY).map(String::as_str) == Some("https") {
+            return true;
+        }
+
+        req.headers
+            .get("X-Forwarded-Proto")
+            .is_some_and(|proto| proto.eq_ignore_ascii_case("https"))
+    }
+}
+
+impl Default for HttpsRedirect {
+    fn default() -> HttpsRedirect {
+        HttpsRedirect::new()
+    }
+}
+
+impl Middleware for HttpsRedirect {
+    fn before(&self, req: &mut Request) -> Option<Response> {
+        if self.is_https(req) {
+            return None;
+        }
+
+        let host = req
+            .headers
+            .get("Host")
+            .or(self.canonical_host.as_deref())?
+            .to_owned();
+
+        // raw_path is the target exactly as sent, query and percent
+        // escapes included, which is what the Location must carry
+        Some(
+            Response::empty(301)
+                .add_header("Location", &format!("https://{}{}", host, req.raw_path)),
+        )
+    }
+
+    fn after(&self, req: &Request, res: Response) -> Response {
+        match self.hsts_max_age {
+            Some(max_age) if self.is_https(req) => res.add_header(
+                "Strict-Transport-Security",
+                &format!("max-age={}", max_age),
+            ),
+            _ => res,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+
+    fn location(res: &Response) -> String {
+        res.headers.get("Location").unwrap().to_owned()
+    }
+
+    #[test]
+    fn redirects_preserving_path() {
+        let mut req = request("GET", "/a/b");
+        req.headers
+            .insert("Host".to_owned(), "example.com".to_owned());
+
+        let res = HttpsRedirect::new().before(&mut req).unwrap();
+        assert_eq!(res.code, 301);
+        assert_eq!(location(&res), "https://example.com/a/b");
+    }
+
+    #[test]
+    fn redirects_preserving_query() {
+        // built like a wire request, so the query is split off `path`
+        // and the escapes are decoded there; the Location must still
+        // carry the original spelling
+        let mut req = Request::builder().get("/search?q=a%20b&page=2").build();
+        req.headers
+            .insert("Host".to_owned(), "example.com".to_owned());
+
+        let res = HttpsRedirect::new().before(&mut req).unwrap();
+        assert_eq!(location(&res), "https://example.com/search?q=a%20b&page=2");
+    }
+
+    #[test]
+    fn forwarded_proto_https_not_redirected() {
+        let mut req = request("GET", "/a");
+        req.headers
+            .insert("Host".to_owned(), "example.com".to_owned());
+        req.headers
+            .insert("X-Forwarded-Proto".to_owned(), "https".to_owned());
+
+        assert!(HttpsRedirect::new().before(&mut req).is_none());
+    }
+
+    #[test]
+    fn falls_back_to_canonical_host() {
+        let mut req = request("GET", "/a");
+        let redirect = HttpsRedirect::new().canonical_host("example.com");
+        let res = redirect.before(&mut req).unwrap();
+        assert_eq!(location(&res), "https://example.com/a");
+    }
+
+    #[test]
+    fn hsts_header_only_on_https() {
+        let redirect = HttpsRedirect::new().hsts(3600);
+
+        let mut req = request("GET", "/a");
+        req.headers
+            .insert("X-Forwarded-Proto".to_owned(), "https".to_owned());
+        let res = redirect.after(&req, Response::empty(200));
+        assert_eq!(
+            res.headers.get("Strict-Transport-Security").unwrap(),
+            "max-age=3600"
+        );
+
+        let plain = request("GET", "/a");
+        let res = redirect.after(&plain, Response::empty(200));
+        assert!(!res.headers.contains_key("Strict-Transport-Security"));
+    }
+}
@@ -0,0 +1,433 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::middleware::Middleware;
+use crate::{Method, Request, Response};
+
+const KEY_EXTENSION: &str = "idempotency.key";
+
+/// Polling step while a duplicate waits on the first execution.
+const CONCURRENT_WAIT_STEP: Duration = Duration::from_millis(5);
+
+/// Replays recorded responses for retried requests.
+///
+/// When a request on a configured method carries an `Idempotency-Key`
+/// header, the first execution's response (status, headers, body) is
+/// recorded in the store keyed by key and route, and later requests
+/// with the same key get that response replayed — with an
+/// `Idempotent-Replayed: true` header — instead of re-running the
+/// handler. A duplicate arriving while the first execution is still
+/// running receives a 409 by default; see [`wait_for_concurrent`] for
+/// a bounded wait instead.
+///
+/// [`wait_for_concurrent`]: IdempotencyKeys::wait_for_concurrent
+///
+/// # Examples
+/// ```
+/// use std::time::Duration;
+/// use http_server_starter_rust::{Router, middleware::{IdempotencyKeys, MemoryIdempotencyStore}};
+///
+/// let mut r = Router::new("127.0.0.1:12345");
+/// r.use_middleware(IdempotencyKeys::new(
+///     MemoryIdempotencyStore::new(Duration::from_secs(24 * 60 * 60)),
+/// ));
+/// ```
+pub struct IdempotencyKeys {
+    store: Box<dyn IdempotencyStore>,
+    methods: Vec<Method>,
+    /// path prefixes the middleware applies to; empty = all
+    routes: Vec<String>,
+    /// how long a duplicate may wait on the first execution; None = 409
+    wait_for_concurrent: Option<Duration>,
+}
+
+/// Storage backend for [`IdempotencyKeys`], so recorded responses can
+/// live in redis or a database instead of process memory.
+pub trait IdempotencyStore: Send + Sync {
+    /// Claims `key` for execution if it is unseen, and reports its
+    /// current state otherwise.
+    fn begin(&self, key: &str) -> BeginOutcome;
+
+    /// Records the response of the execution that claimed `key`.
+    fn complete(&self, key: &str, response: StoredResponse);
+
+    /// Drops the claim on `key` without recording a response, so a
+    /// retry re-runs the handler; used when the response cannot replay
+    /// (a streamed body is consumed on its way to the first client).
+    fn forget(&self, key: &str);
+}
+
+/// What [`IdempotencyStore::begin`] found for a key.
+pub enum BeginOutcome {
+    /// Unseen; the caller now owns the execution
+    New,
+    /// A previous execution finished inside the TTL
+    Replay(StoredResponse),
+    /// Another execution with this key is still running
+    InFlight,
+}
+
+/// The recorded parts of a first execution's response.
+#[derive(Clone)]
+pub struct StoredResponse {
+    pub code: u16,
+    pub headers: crate::Headers,
+    /// Raw body bytes, so binary responses replay exactly and the
+    /// recorded `Content-Length` keeps framing them correctly
+    pub body: Option<Vec<u8>>,
+}
+
+impl IdempotencyKeys {
+    /// Returns new IdempotencyKeys applying to POST requests on every
+    /// route.
+    pub fn new(store: impl IdempotencyStore + 'static) -> IdempotencyKeys {
+        IdempotencyKeys {
+            store: Box::new(store),
+            methods: vec![Method::Post],
+            routes: vec![],
+            wait_for_concurrent: None,
+        }
+    }
+
+    /// Sets the methods the middleware applies to.
+    pub fn methods(mut self, methods: Vec<impl Into<Method>>) -> IdempotencyKeys {
+        self.methods = methods.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Restricts the middleware to paths under any of the given
+    /// prefixes.
+    pub fn routes(mut self, prefixes: Vec<&str>) -> IdempotencyKeys {
+        self.routes = prefixes.into_iter().map(|p| p.to_owned()).collect();
+        self
+    }
+
+    /// Makes concurrent duplicates wait up to `timeout` for the first
+    /// execution and replay its response; past the timeout they get
+    /// the 409. On a current-thread runtime duplicates never wait —
+    /// the first execution may be parked behind them on the same
+    /// thread, so waiting could only deadlock.
+    pub fn wait_for_concurrent(mut self, timeout: Duration) -> IdempotencyKeys {
+        self.wait_for_concurrent = Some(timeout);
+        self
+    }
+
+    fn applies_to(&self, req: &Request) -> bool {
+        self.methods.contains(&req.method)
+            && (self.routes.is_empty()
+                || self.routes.iter().any(|p| req.path.starts_with(p.as_str())))
+    }
+
+    fn replay(&self, stored: StoredResponse) -> Response {
+        let mut res = Response::empty(stored.code);
+        res.headers = stored.headers;
+        res.data = stored.body.map(crate::ResponseData::Bytes);
+        res.add_header("Idempotent-Replayed", "true")
+    }
+}
+
+/// Sleeps one polling step toward `deadline`, handing the worker
+/// thread back to the runtime where possible. Returns false once
+/// waiting is pointless: the deadline has passed, or this is a
+/// current-thread tokio runtime, where blocking would starve the very
+/// execution being waited on.
+fn wait_step(deadline: Instant) -> bool {
+    if Instant::now() >= deadline {
+        return false;
+    }
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle)
+            if matches!(
+                handle.runtime_flavor(),
+                tokio::runtime::RuntimeFlavor::CurrentThread
+            ) =>
+        {
+            false
+        }
+        Ok(_) => {
+            tokio::task::block_in_place(|| std::thread::sleep(CONCURRENT_WAIT_STEP));
+            true
+        }
+        Err(_) => {
+            std::thread::sleep(CONCURRENT_WAIT_STEP);
+            true
+        }
+    }
+}
+
+impl Middleware for IdempotencyKeys {
+    fn before(&self, req: &mut Request) -> Option<Response> {
+        if !self.applies_to(req) {
+            return None;
+        }
+        let key = req.headers.get("Idempotency-Key")?;
+        let key = format!("{}\n{}", key, req.path);
+
+        let deadline = self.wait_for_concurrent.map(|t| Instant::now() + t);
+        loop {
+            match self.store.begin(&key) {
+                BeginOutcome::New => {
+                    req.extensions.insert(KEY_EXTENSION.to_owned(), key);
+                    return None;
+                }
+                BeginOutcome::Replay(stored) => return Some(self.replay(stored)),
+                BeginOutcome::InFlight if deadline.is_some_and(wait_step) => {}
+                BeginOutcome::InFlight => {
+                    return Some(Response::new(
+                        409,
+                        "a request with this idempotency key is in flight",
+                    ));
+                }
+            }
+        }
+    }
+
+    fn after(&self, req: &Request, res: Response) -> Response {
+        if let Some(key) = req.extensions.get(KEY_EXTENSION) {
+            // a stream is consumed on its way to this client and
+            // cannot replay; drop the claim so a retry re-runs
+            if res.is_stream() {
+                self.store.forget(key);
+            } else {
+                self.store.complete(
+                    key,
+                    StoredResponse {
+                        code: res.code,
+                        headers: res.headers.clone(),
+                        body: res.data.as_ref().map(|d| d.to_bytes()),
+                    },
+                );
+            }
+        }
+        res
+    }
+}
+
+/// In-process [`IdempotencyStore`] with per-entry TTL.
+pub struct MemoryIdempotencyStore {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, State>>,
+}
+
+enum State {
+    InFlight,
+    Done {
+        response: StoredResponse,
+        expires: Instant,
+    },
+}
+
+impl MemoryIdempotencyStore {
+    /// Returns new MemoryIdempotencyStore whose recorded responses
+    /// expire after `ttl`.
+    pub fn new(ttl: Duration) -> MemoryIdempotencyStore {
+        MemoryIdempotencyStore {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+/// Drops every `Done` entry past its TTL, so the map does not grow
+/// without bound under a stream of unique keys.
+fn sweep_expired(entries: &mut HashMap<String, State>) {
+    let now = Instant::now();
+    entries.retain(|_, state| match state {
+        State::InFlight => true,
+        State::Done { expires, .. } => *expires > now,
+    });
+}
+
+impl IdempotencyStore for MemoryIdempotencyStore {
+    fn begin(&self, key: &str) -> BeginOutcome {
+        let mut entries = self.entries.lock().unwrap();
+        sweep_expired(&mut entries);
+        match entries.get(key) {
+            Some(State::Done { response, .. }) => BeginOutcome::Replay(response.clone()),
+            Some(State::InFlight) => BeginOutcome::InFlight,
+            None => {
+                entries.insert(key.to_owned(), State::InFlight);
+                BeginOutcome::New
+            }
+        }
+    }
+
+    fn complete(&self, key: &str, response: StoredResponse) {
+        let mut entries = self.entries.lock().unwrap();
+        sweep_expired(&mut entries);
+        entries.insert(
+            key.to_owned(),
+            State::Done {
+                response,
+                expires: Instant::now() + self.ttl,
+            },
+        );
+    }
+
+    fn forget(&self, key: &str) {
+        self.entries.lock().unwrap().remove(key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    fn keyed_request(key: &str) -> Request {
+        let mut req = request("POST", "/orders");
+        req.headers.insert("Idempotency-Key", key);
+        req
+    }
+
+    fn middleware(ttl: Duration) -> IdempotencyKeys {
+        IdempotencyKeys::new(MemoryIdempotencyStore::new(ttl))
+    }
+
+    #[test]
+    fn second_request_replays_the_recorded_response() {
+        let keys = middleware(Duration::from_secs(60));
+
+        let mut req = keyed_request("k1");
+        assert!(keys.before(&mut req).is_none());
+        keys.after(&req, Response::new(201, "created #42"));
+
+        let mut retry = keyed_request("k1");
+        let res = keys.before(&mut retry).expect("should replay");
+        assert_eq!(res.code, 201);
+        assert_eq!(res.headers.get("Idempotent-Replayed").unwrap(), "true");
+        assert_eq!(res.data.unwrap().text(), "created #42");
+    }
+
+    #[test]
+    fn requests_without_a_key_always_run() {
+        let keys = middleware(Duration::from_secs(60));
+        let mut req = request("POST", "/orders");
+        assert!(keys.before(&mut req).is_none());
+        keys.after(&req, Response::empty(201));
+        let mut req = request("POST", "/orders");
+        assert!(keys.before(&mut req).is_none());
+    }
+
+    #[test]
+    fn expired_entries_re_run_the_handler() {
+        let keys = middleware(Duration::from_millis(10));
+
+        let mut req = keyed_request("k1");
+        assert!(keys.before(&mut req).is_none());
+        keys.after(&req, Response::empty(201));
+
+        std::thread::sleep(Duration::from_millis(20));
+        let mut retry = keyed_request("k1");
+        assert!(keys.before(&mut retry).is_none());
+    }
+
+    #[test]
+    fn concurrent_duplicate_gets_409_by_default() {
+        let keys = middleware(Duration::from_secs(60));
+
+        // first execution claimed the key but has not completed
+        let mut req = keyed_request("k1");
+        assert!(keys.before(&mut req).is_none());
+
+        let mut dup = keyed_request("k1");
+        let res = keys.before(&mut dup).expect("should conflict");
+        assert_eq!(res.code, 409);
+    }
+
+    #[test]
+    fn concurrent_duplicate_can_wait_for_the_first_execution() {
+        let keys = Arc::new(
+            middleware(Duration::from_secs(60)).wait_for_concurrent(Duration::from_secs(1)),
+        );
+
+        let mut req = keyed_request("k1");
+        assert!(keys.before(&mut req).is_none());
+
+        let worker = {
+            let keys = Arc::clone(&keys);
+            std::thread::spawn(move || {
+                std::thread::sleep(Duration::from_millis(20));
+                keys.after(&req, Response::new(201, "created"));
+            })
+        };
+
+        let mut dup = keyed_request("k1");
+        let res = keys.before(&mut dup).expect("should replay after waiting");
+        assert_eq!(res.code, 201);
+        assert_eq!(res.headers.get("Idempotent-Replayed").unwrap(), "true");
+        worker.join().unwrap();
+    }
+
+    #[test]
+    fn waiting_gives_up_with_a_409_at_the_deadline() {
+        let keys = middleware(Duration::from_secs(60)).wait_for_concurrent(Duration::from_millis(30));
+
+        // the first execution never completes
+        let mut req = keyed_request("k1");
+        assert!(keys.before(&mut req).is_none());
+
+        let mut dup = keyed_request("k1");
+        let res = keys.before(&mut dup).expect("should give up");
+        assert_eq!(res.code, 409);
+    }
+
+    #[test]
+    fn binary_responses_replay_byte_for_byte_and_streams_are_not_recorded() {
+        let keys = middleware(Duration::from_secs(60));
+        let png = vec![0x89, b'P', b'N', b'G', 0x00, 0xff];
+
+        let mut req = keyed_request("k1");
+        assert!(keys.before(&mut req).is_none());
+        keys.after(&req, Response::bytes(201, png.clone()));
+
+        // the recorded Content-Length must still frame the bytes
+        let mut retry = keyed_request("k1");
+        let res = keys.before(&mut retry).expect("should replay");
+        assert_eq!(res.headers.get("Content-Length").unwrap(), "6");
+        assert_eq!(res.data.unwrap().to_bytes(), png);
+
+        // a stream cannot replay; the claim is dropped and a retry
+        // re-runs the handler
+        let mut req = keyed_request("k2");
+        assert!(keys.before(&mut req).is_none());
+        keys.after(&req, Response::stream(201, std::io::Cursor::new(b"live".to_vec())));
+        let mut retry = keyed_request("k2");
+        assert!(keys.before(&mut retry).is_none());
+    }
+
+    #[test]
+    fn expired_entries_are_swept_from_the_store() {
+        let store = MemoryIdempotencyStore::new(Duration::from_millis(10));
+        assert!(matches!(store.begin("k1"), BeginOutcome::New));
+        store.complete(
+            "k1",
+            StoredResponse {
+                code: 201,
+                headers: crate::Headers::new(),
+                body: None,
+            },
+        );
+
+        std::thread::sleep(Duration::from_millis(20));
+        // touching any key reclaims the expired entry's memory
+        assert!(matches!(store.begin("k2"), BeginOutcome::New));
+        let entries = store.entries.lock().unwrap();
+        assert_eq!(entries.len(), 1, "only k2's in-flight claim remains");
+    }
+
+    #[test]
+    fn unconfigured_methods_and_routes_pass_through() {
+        let keys = middleware(Duration::from_secs(60)).routes(vec!["/api"]);
+
+        let mut req = keyed_request("k1");
+        assert!(keys.before(&mut req).is_none(), "outside /api");
+
+        let mut req = request("GET", "/api/orders");
+        req.headers.insert("Idempotency-Key", "k1");
+        assert!(keys.before(&mut req).is_none(), "GET not configured");
+    }
+}
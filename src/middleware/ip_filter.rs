@@ -0,0 +1,211 @@
+use std::net::IpAddr;
+
+use crate::middleware::Middleware;
+use crate::{Request, Response};
+
+/// Restricts access by client IP address.
+///
+/// Configured with allow and deny lists of IPs and CIDR ranges, evaluated
+/// against [`Request::remote_addr`]. Deny takes precedence over allow, and
+/// an empty allow list means "allow all except denied". Rejections get a
+/// 403. Attach globally with [`Router::use_middleware`], optionally scoped
+/// to a path prefix for admin-only sections.
+///
+/// [`Router::use_middleware`]: crate::Router::use_middleware
+///
+/// # Examples
+/// ```
+/// use http_server_starter_rust::{Router, middleware::IpFilter};
+///
+/// let mut r = Router::new("127.0.0.1:12345");
+/// r.use_middleware(
+///     IpFilter::new()
+///         .allow("10.0.0.0/8")
+///         .deny("10.1.2.3")
+///         .scope("/admin/"),
+/// );
+/// ```
+pub struct IpFilter {
+    allow: Vec<Cidr>,
+    deny: Vec<Cidr>,
+    scope: Option<String>,
+}
+
+impl IpFilter {
+    /// Returns new IpFilter allowing everything.
+    pub fn new() -> IpFilter {
+        IpFilter {
+            allow: vec![],
+            deny: vec![],
+            scope: None,
+        }
+    }
+
+    /// Adds an IP or CIDR range to the allow list.
+    ///
+    /// # Panics
+    /// Panics if `range` is not a valid IP or CIDR range.
+    pub fn allow(mut self, range: &str) -> IpFilter {
+        self.allow.push(Cidr::parse(range).expect("invalid range"));
+        self
+    }
+
+    /// Adds an IP or CIDR range to the deny list.
+    ///
+    /// # Panics
+    /// Panics if `range` is not a valid IP or CIDR range.
+    pub fn deny(mut self, range: &str) -> IpFilter {
+        self.deny.push(Cidr::parse(range).expect("invalid range"));
+        self
+    }
+
+    /// Restricts the filter to paths starting with `prefix`.
+    pub fn scope(mut self, prefix: &str) -> IpFilter {
+        self.scope = Some(prefix.to_owned());
+        self
+    }
+
+    fn permits(&self, ip: IpAddr) -> bool {
+        if self.deny.iter().any(|c| c.contains(ip)) {
+            return false;
+        }
+
+        self.allow.is_empty() || self.allow.iter().any(|c| c.contains(ip))
+    }
+}
+
+impl Default for IpFilter {
+    fn default() -> IpFilter {
+        IpFilter::new()
+    }
+}
+
+impl Middleware for IpFilter {
+    fn before(&self, req: &mut Request) -> Option<Response> {
+        if let Some(ref prefix) = self.scope {
+            if !req.path.starts_with(prefix) {
+                return None;
+            }
+        }
+
+        let ip = match req.remote_addr {
+            Some(addr) => addr.ip(),
+            None => return None,
+        };
+
+        if self.permits(ip) {
+            None
+        } else {
+            Some(Response::new(403, "forbidden"))
+        }
+    }
+}
+
+/// An IP network in CIDR notation, e.g. `10.0.0.0/8` or `2001:db8::/32`.
+///
+/// A bare address parses as a full-length prefix.
+#[derive(Debug, Clone)]
+struct Cidr {
+    addr: IpAddr,
+    prefix_len: u8,
+}
+
+impl Cidr {
+    fn parse(range: &str) -> Option<Cidr> {
+        let (addr, prefix_len) = match range.split_once('/') {
+            Some((addr, len)) => (addr.parse().ok()?, len.parse().ok()?),
+            None => {
+                let addr: IpAddr = range.parse().ok()?;
+                let len = if addr.is_ipv4() { 32 } else { 128 };
+                (addr, len)
+            }
+        };
+
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix_len > max {
+            return None;
+        }
+
+        Some(Cidr { addr, prefix_len })
+    }
+
+    fn contains(&self, ip: IpAddr) -> bool {
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                prefix_match(&net.octets(), &ip.octets(), self.prefix_len)
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                prefix_match(&net.octets(), &ip.octets(), self.prefix_len)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Compares the first `prefix_len` bits of two addresses.
+fn prefix_match(net: &[u8], ip: &[u8], prefix_len: u8) -> bool {
+    let full_bytes = (prefix_len / 8) as usize;
+    if net[..full_bytes] != ip[..full_bytes] {
+        return false;
+    }
+
+    let rem = prefix_len % 8;
+    if rem == 0 {
+        return true;
+    }
+
+    let mask = 0xffu8 << (8 - rem);
+    (net[full_bytes] & mask) == (ip[full_bytes] & mask)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+
+    fn request(addr: &str) -> Request {
+        let mut req = crate::middleware::test_util::request("GET", "/admin/users");
+        req.remote_addr = Some(addr.parse::<SocketAddr>().unwrap());
+        req
+    }
+
+    fn rejected(filter: &IpFilter, addr: &str) -> bool {
+        filter.before(&mut request(addr)).is_some()
+    }
+
+    #[test]
+    fn v4_range() {
+        let filter = IpFilter::new().allow("10.0.0.0/8");
+        assert!(!rejected(&filter, "10.1.2.3:80"));
+        assert!(rejected(&filter, "11.0.0.1:80"));
+    }
+
+    #[test]
+    fn v6_range() {
+        let filter = IpFilter::new().allow("2001:db8::/32");
+        assert!(!rejected(&filter, "[2001:db8::1]:80"));
+        assert!(rejected(&filter, "[2001:db9::1]:80"));
+    }
+
+    #[test]
+    fn deny_takes_precedence_over_allow() {
+        let filter = IpFilter::new().allow("10.0.0.0/8").deny("10.1.2.3");
+        assert!(rejected(&filter, "10.1.2.3:80"));
+        assert!(!rejected(&filter, "10.1.2.4:80"));
+    }
+
+    #[test]
+    fn empty_allow_means_allow_all_except_denied() {
+        let filter = IpFilter::new().deny("192.168.0.0/16");
+        assert!(!rejected(&filter, "10.0.0.1:80"));
+        assert!(rejected(&filter, "192.168.1.1:80"));
+    }
+
+    #[test]
+    fn scoped_filter_ignores_other_paths() {
+        let filter = IpFilter::new().allow("10.0.0.0/8").scope("/admin/");
+        let mut req = request("11.0.0.1:80");
+        req.path = "/public".to_owned();
+        assert!(filter.before(&mut req).is_none());
+    }
+}
@@ -0,0 +1,203 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::middleware::Middleware;
+use crate::{crypto, json, Request, Response};
+
+/// Prefix under which decoded claims land in [`Request::extensions`],
+/// e.g. the `sub` claim becomes `jwt.sub`.
+pub const CLAIMS_PREFIX: &str = "jwt.";
+
+/// Validates `Authorization: Bearer <jwt>` tokens (HS256).
+///
+/// Verifies the HMAC-SHA256 signature and the `exp`/`nbf` claims (with
+/// configurable leeway), placing the decoded claims into request
+/// extensions under a `jwt.` prefix. Missing or invalid tokens get a 401
+/// with `WWW-Authenticate: Bearer error="invalid_token"`. Routes can be
+/// excluded (e.g. the login endpoint).
+///
+/// # Examples
+/// ```
+/// use http_server_starter_rust::{Router, middleware::JwtAuth};
+///
+/// let mut r = Router::new("127.0.0.1:12345");
+/// r.use_middleware(JwtAuth::new(b"hs256-secret").exempt("/login"));
+/// ```
+pub struct JwtAuth {
+    secret: Vec<u8>,
+    leeway: u64,
+    exempt: Vec<String>,
+}
+
+impl JwtAuth {
+    /// Returns new JwtAuth verifying tokens with `secret`.
+    pub fn new(secret: &[u8]) -> JwtAuth {
+        JwtAuth {
+            secret: secret.to_vec(),
+            leeway: 30,
+            exempt: vec![],
+        }
+    }
+
+    /// Sets the clock leeway (in seconds) for `exp`/`nbf` checks.
+    pub fn leeway(mut self, leeway: u64) -> JwtAuth {
+        self.leeway = leeway;
+        self
+    }
+
+    /// Excludes paths starting with `prefix` from token checks.
+    pub fn exempt(mut self, prefix: &str) -> JwtAuth {
+        self.exempt.push(prefix.to_owned());
+        self
+    }
+
+    /// Verifies a compact JWT, returning its claims.
+    fn verify(&self, token: &str) -> Option<std::collections::HashMap<String, String>> {
+        let mut parts = token.split('.');
+        let (header, payload, signature) = (parts.next()?, parts.next()?, parts.next()?);
+        if parts.next().is_some() {
+            return None;
+        }
+
+        let signature = crypto::base64url_decode(signature)?;
+        let signed = &token[..header.len() + 1 + payload.len()];
+        let expected = crypto::hmac_sha256(&self.secret, signed.as_bytes());
+        if !crypto::constant_time_eq(&signature, &expected) {
+            return None;
+        }
+
+        let header = crypto::base64url_decode(header)?;
+        let header = json::parse_object(std::str::from_utf8(&header).ok()?)?;
+        if header.get("alg").map(String::as_str) != Some("HS256") {
+            return None;
+        }
+
+        let payload = crypto::base64url_decode(payload)?;
+        let claims = json::parse_object(std::str::from_utf8(&payload).ok()?)?;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        if let Some(exp) = claims.get("exp") {
+            let exp: u64 = exp.parse().ok()?;
+            if now > exp + self.leeway {
+                return None;
+            }
+        }
+        if let Some(nbf) = claims.get("nbf") {
+            let nbf: u64 = nbf.parse().ok()?;
+            if now + self.leeway < nbf {
+                return None;
+            }
+        }
+
+        Some(claims)
+    }
+}
+
+impl Middleware for JwtAuth {
+    fn before(&self, req: &mut Request) -> Option<Response> {
+        if self.exempt.iter().any(|p| req.path.starts_with(p)) {
+            return None;
+        }
+
+        let token = req
+            .headers
+            .get("Authorization")
+            .and_then(|auth| auth.strip_prefix("Bearer "));
+
+        match token.and_then(|t| self.verify(t)) {
+            Some(claims) => {
+                for (name, value) in claims {
+                    req.extensions
+                        .insert(format!("{}{}", CLAIMS_PREFIX, name), value);
+                }
+                None
+            }
+            None => Some(Response::new(401, "unauthorized").add_header(
+                "WWW-Authenticate",
+                "Bearer error=\"invalid_token\"",
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+
+    const SECRET: &[u8] = b"test-secret";
+
+    fn token(claims: &str) -> String {
+        let head = crypto::base64url_encode(br#"{"alg":"HS256","typ":"JWT"}"#);
+        let payload = crypto::base64url_encode(claims.as_bytes());
+        let signed = format!("{}.{}", head, payload);
+        let sig = crypto::base64url_encode(&crypto::hmac_sha256(SECRET, signed.as_bytes()));
+        format!("{}.{}", signed, sig)
+    }
+
+    fn bearer_request(token: &str) -> Request {
+        let mut req = request("GET", "/api/me");
+        req.headers
+            .insert("Authorization".to_owned(), format!("Bearer {}", token));
+        req
+    }
+
+    fn now() -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs()
+    }
+
+    #[test]
+    fn valid_token_passes_with_claims() {
+        let auth = JwtAuth::new(SECRET);
+        let token = token(&format!(r#"{{"sub":"user1","exp":{}}}"#, now() + 3600));
+        let mut req = bearer_request(&token);
+
+        assert!(auth.before(&mut req).is_none());
+        assert_eq!(req.extensions.get("jwt.sub").unwrap(), "user1");
+    }
+
+    #[test]
+    fn expired_token_rejected() {
+        let auth = JwtAuth::new(SECRET).leeway(0);
+        let token = token(&format!(r#"{{"sub":"user1","exp":{}}}"#, now() - 3600));
+        let mut req = bearer_request(&token);
+
+        let res = auth.before(&mut req).expect("should reject");
+        assert_eq!(res.code, 401);
+        assert_eq!(
+            res.headers.get("WWW-Authenticate").unwrap(),
+            "Bearer error=\"invalid_token\""
+        );
+    }
+
+    #[test]
+    fn tampered_signature_rejected() {
+        let auth = JwtAuth::new(SECRET);
+        let mut token = token(r#"{"sub":"user1"}"#);
+        token.pop();
+        token.push('A');
+        let mut req = bearer_request(&token);
+
+        assert!(auth.before(&mut req).is_some());
+    }
+
+    #[test]
+    fn missing_token_rejected() {
+        let auth = JwtAuth::new(SECRET);
+        let mut req = request("GET", "/api/me");
+        assert!(auth.before(&mut req).is_some());
+    }
+
+    #[test]
+    fn exempt_path_passes_without_token() {
+        let auth = JwtAuth::new(SECRET).exempt("/login");
+        let mut req = request("POST", "/login");
+        assert!(auth.before(&mut req).is_none());
+    }
+}
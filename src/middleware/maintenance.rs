@@ -0,0 +1,175 @@
+use std::net::IpAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use crate::middleware::Middleware;
+use crate::{Request, Response};
+
+/// Flips the whole server into maintenance mode at runtime.
+///
+/// While enabled, every request short-circuits to a 503 with a
+/// `Retry-After` header, except for an allowlist of paths (health
+/// checks) and optionally of client IPs (to verify a deploy). The flag
+/// is a single atomic load on the hot path. Clone the middleware before
+/// attaching it to keep a handle for toggling.
+///
+/// # Examples
+/// ```
+/// use http_server_starter_rust::{Router, middleware::Maintenance};
+///
+/// let mut r = Router::new("127.0.0.1:12345");
+/// let maintenance = Maintenance::new().allow_path("/health");
+/// r.use_middleware(maintenance.clone());
+///
+/// // later, during a deploy:
+/// maintenance.set_enabled(true);
+/// ```
+#[derive(Clone)]
+pub struct Maintenance {
+    state: Arc<MaintenanceState>,
+}
+
+struct MaintenanceState {
+    enabled: AtomicBool,
+    allow_paths: Vec<String>,
+    allow_ips: Vec<IpAddr>,
+    retry_after: u64,
+    body: String,
+}
+
+impl Maintenance {
+    /// Returns new Maintenance middleware, initially disabled.
+    pub fn new() -> Maintenance {
+        Maintenance {
+            state: Arc::new(MaintenanceState {
+                enabled: AtomicBool::new(false),
+                allow_paths: vec![],
+                allow_ips: vec![],
+                retry_after: 300,
+                body: "service temporarily unavailable".to_owned(),
+            }),
+        }
+    }
+
+    /// Turns maintenance mode on or off at runtime.
+    pub fn set_enabled(&self, enabled: bool) {
+        self.state.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Whether maintenance mode is currently on.
+    pub fn enabled(&self) -> bool {
+        self.state.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Exempts paths starting with `prefix` (e.g. health checks).
+    pub fn allow_path(mut self, prefix: &str) -> Maintenance {
+        self.configure().allow_paths.push(prefix.to_owned());
+        self
+    }
+
+    /// Exempts a client IP so the deploy can be verified.
+    ///
+    /// # Panics
+    /// Panics if `ip` is not a valid IP address.
+    pub fn allow_ip(mut self, ip: &str) -> Maintenance {
+        let ip = ip.parse().expect("invalid ip address");
+        self.configure().allow_ips.push(ip);
+        self
+    }
+
+    /// Sets the `Retry-After` value (in seconds) on 503 responses.
+    pub fn retry_after(mut self, seconds: u64) -> Maintenance {
+        self.configure().retry_after = seconds;
+        self
+    }
+
+    /// Sets the 503 response body.
+    pub fn body(mut self, body: &str) -> Maintenance {
+        self.configure().body = body.to_owned();
+        self
+    }
+
+    fn configure(&mut self) -> &mut MaintenanceState {
+        Arc::get_mut(&mut self.state).expect("configure Maintenance before cloning or attaching")
+    }
+}
+
+impl Default for Maintenance {
+    fn default() -> Maintenance {
+        Maintenance::new()
+    }
+}
+
+impl Middleware for Maintenance {
+    fn before(&self, req: &mut Request) -> Option<Response> {
+        if !self.state.enabled.load(Ordering::Relaxed) {
+            return None;
+        }
+
+        if self
+            .state
+            .allow_paths
+            .iter()
+            .any(|p| req.path.starts_with(p))
+        {
+            return None;
+        }
+
+        if let Some(addr) = req.remote_addr {
+            if self.state.allow_ips.contains(&addr.ip()) {
+                return None;
+            }
+        }
+
+        Some(
+            Response::new(503, self.state.body.clone())
+                .add_header("Retry-After", &self.state.retry_after.to_string()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn toggles_on_and_off() {
+        let maintenance = Maintenance::new().retry_after(60);
+        let attached = maintenance.clone();
+
+        assert!(attached.before(&mut request("GET", "/")).is_none());
+
+        maintenance.set_enabled(true);
+        let res = attached.before(&mut request("GET", "/")).unwrap();
+        assert_eq!(res.code, 503);
+        assert_eq!(res.headers.get("Retry-After").unwrap(), "60");
+
+        maintenance.set_enabled(false);
+        assert!(attached.before(&mut request("GET", "/")).is_none());
+    }
+
+    #[test]
+    fn allowlisted_path_passes() {
+        let maintenance = Maintenance::new().allow_path("/health");
+        maintenance.set_enabled(true);
+
+        assert!(maintenance.before(&mut request("GET", "/health")).is_none());
+        assert!(maintenance.before(&mut request("GET", "/app")).is_some());
+    }
+
+    #[test]
+    fn allowlisted_ip_passes() {
+        let maintenance = Maintenance::new().allow_ip("10.0.0.1");
+        maintenance.set_enabled(true);
+
+        let mut req = request("GET", "/app");
+        req.remote_addr = Some("10.0.0.1:9999".parse().unwrap());
+        assert!(maintenance.before(&mut req).is_none());
+
+        let mut req = request("GET", "/app");
+        req.remote_addr = Some("10.0.0.2:9999".parse().unwrap());
+        assert!(maintenance.before(&mut req).is_some());
+    }
+}
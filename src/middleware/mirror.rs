@@ -0,0 +1,340 @@
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+
+use tokio::sync::mpsc;
+
+use crate::client;
+use crate::middleware::Middleware;
+use crate::{Method, Request, Response};
+
+/// Shadows a share of production traffic to another backend.
+///
+/// Matching requests are copied (head and body) and replayed against an
+/// upstream base URL through the minimal [`client`], queued on a bounded
+/// background channel so the primary request never waits on the mirror.
+/// The mirror's response is discarded, or printed to stderr with
+/// [`log_responses`]. When the queue is full or the upstream errors the
+/// copy is dropped and counted; the primary response is unaffected
+/// either way. [`stats`] exposes the mirrored/dropped counters.
+///
+/// `new` spawns the background sender, so it must be called from within
+/// a tokio runtime.
+///
+/// [`log_responses`]: Mirror::log_responses
+/// [`stats`]: Mirror::stats
+///
+/// # Examples
+/// ```no_run
+/// use http_server_starter_rust::{Router, middleware::Mirror};
+///
+/// # async fn setup() {
+/// let mut r = Router::new("127.0.0.1:12345");
+/// let mirror = Mirror::new("http://10.0.0.7:8080", 64)
+///     .sample_rate(0.1)
+///     .routes(vec!["/api"]);
+/// let stats = mirror.stats();
+/// r.use_middleware(mirror);
+/// # }
+/// ```
+pub struct Mirror {
+    tx: mpsc::Sender<Job>,
+    sample_rate: f64,
+    /// methods the middleware applies to; empty = all
+    methods: Vec<Method>,
+    /// path prefixes the middleware applies to; empty = all
+    routes: Vec<String>,
+    seen: AtomicU64,
+    log: Arc<AtomicBool>,
+    stats: MirrorStats,
+}
+
+/// Shared mirrored/dropped counters for a [`Mirror`]; clones observe
+/// the same counts after the middleware is handed to the router.
+#[derive(Clone, Default)]
+pub struct MirrorStats {
+    mirrored: Arc<AtomicU64>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl MirrorStats {
+    /// Copies sent to the upstream that produced a response.
+    pub fn mirrored(&self) -> u64 {
+        self.mirrored.load(Ordering::Relaxed)
+    }
+
+    /// Copies discarded because the queue was full or the upstream
+    /// errored.
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+}
+
+struct Job {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Mirror {
+    /// Returns new Mirror replaying to `upstream` (a base URL like
+    /// `http://host:port`) with the given queue depth, and spawns the
+    /// background sender.
+    pub fn new(upstream: &str, queue_depth: usize) -> Mirror {
+        let (tx, rx) = mpsc::channel(queue_depth);
+        let stats = MirrorStats::default();
+        let log = Arc::new(AtomicBool::new(false));
+        tokio::spawn(run_sender(
+            rx,
+            upstream.trim_end_matches('/').to_owned(),
+            stats.clone(),
+            Arc::clone(&log),
+        ));
+
+        Mirror {
+            tx,
+            sample_rate: 1.0,
+            methods: vec![],
+            routes: vec![],
+            seen: AtomicU64::new(0),
+            log,
+            stats,
+        }
+    }
+
+    /// Sets the fraction of matching requests to mirror, between 0.0
+    /// and 1.0. Sampling is a deterministic stride over the request
+    /// count, not random.
+    pub fn sample_rate(mut self, rate: f64) -> Mirror {
+        self.sample_rate = rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Restricts mirroring to the given methods.
+    pub fn methods(mut self, methods: Vec<impl Into<Method>>) -> Mirror {
+        self.methods = methods.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Restricts mirroring to paths under any of the given prefixes.
+    pub fn routes(mut self, prefixes: Vec<&str>) -> Mirror {
+        self.routes = prefixes.into_iter().map(|p| p.to_owned()).collect();
+        self
+    }
+
+    /// Prints each mirror response (or failure) to stderr.
+    pub fn log_responses(self) -> Mirror {
+        self.log.store(true, Ordering::Relaxed);
+        self
+    }
+
+    /// Returns a handle on the mirrored/dropped counters.
+    pub fn stats(&self) -> MirrorStats {
+        self.stats.clone()
+    }
+
+    fn applies_to(&self, req: &Request) -> bool {
+        (self.methods.is_empty() || self.methods.contains(&req.method))
+            && (self.routes.is_empty()
+                || self.routes.iter().any(|p| req.path.starts_with(p.as_str())))
+    }
+
+    /// Deterministic stride sampling: the n-th matching request is
+    /// mirrored iff the accumulated quota crosses a whole number.
+    fn sampled(&self) -> bool {
+        let n = self.seen.fetch_add(1, Ordering::Relaxed);
+        (n as f64 * self.sample_rate) as u64 != ((n + 1) as f64 * self.sample_rate) as u64
+    }
+}
+
+impl Middleware for Mirror {
+    fn before(&self, req: &mut Request) -> Option<Response> {
+        if !self.applies_to(req) || !self.sampled() {
+            return None;
+        }
+
+        // the client writes its own Host and Content-Length
+        let headers = req
+            .headers
+            .iter()
+            .filter(|(key, _)| {
+                !key.eq_ignore_ascii_case("host") && !key.eq_ignore_ascii_case("content-length")
+            })
+            .map(|(key, val)| (key.to_owned(), val.to_owned()))
+            .collect();
+
+        let job = Job {
+            method: req.method.to_string(),
+            path: req.raw_path.clone(),
+            headers,
+            body: req.body.clone(),
+        };
+        if self.tx.try_send(job).is_err() {
+            self.stats.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        None
+    }
+}
+
+/// Drains the queue, replaying each copy against the upstream.
+async fn run_sender(
+    mut rx: mpsc::Receiver<Job>,
+    upstream: String,
+    stats: MirrorStats,
+    log: Arc<AtomicBool>,
+) {
+    while let Some(job) = rx.recv().await {
+        let log = log.load(Ordering::Relaxed);
+        let url = format!("{}{}", upstream, job.path);
+        let headers: Vec<(&str, &str)> = job
+            .headers
+            .iter()
+            .map(|(key, val)| (key.as_str(), val.as_str()))
+            .collect();
+
+        match client::request(&job.method, &url, &headers, &job.body).await {
+            Ok(res) => {
+                stats.mirrored.fetch_add(1, Ordering::Relaxed);
+                if log {
+                    eprintln!("mirror: {} {} -> {}", job.method, url, res.status);
+                }
+            }
+            Err(err) => {
+                stats.dropped.fetch_add(1, Ordering::Relaxed);
+                if log {
+                    eprintln!("mirror: {} {} failed: {}", job.method, url, err);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use crate::{Response, Router};
+    use pretty_assertions::assert_eq;
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+    use tokio::io::AsyncReadExt;
+
+    static CAPTURED: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    fn capture_upstream(req: &crate::Request) -> Response {
+        CAPTURED
+            .lock()
+            .unwrap()
+            .push(format!(
+                "{} {} {}",
+                req.method,
+                req.raw_path,
+                String::from_utf8_lossy(&req.body)
+            ));
+        Response::new(200, "mirrored")
+    }
+
+    fn captured_under(prefix: &str) -> Vec<String> {
+        CAPTURED
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|line| line.split(' ').nth(1).unwrap().starts_with(prefix))
+            .cloned()
+            .collect()
+    }
+
+    async fn upstream(addr: &str, paths: &[&str]) {
+        let mut r = Router::new(addr);
+        for path in paths {
+            r.handle_func(path, capture_upstream, vec!["GET", "POST"]);
+        }
+        tokio::spawn(async move { r.serve().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    #[tokio::test]
+    async fn mirrors_a_copy_without_touching_the_primary() {
+        let addr = "127.0.0.1:48261";
+        upstream(addr, &["/shadow/orders"]).await;
+
+        let mirror = Mirror::new(&format!("http://{}", addr), 8);
+        let stats = mirror.stats();
+
+        let mut req = request("POST", "/shadow/orders");
+        req.body = b"qty=2".to_vec();
+        let started = Instant::now();
+        assert!(mirror.before(&mut req).is_none());
+        assert!(started.elapsed() < Duration::from_millis(50), "must not block");
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(captured_under("/shadow/"), vec!["POST /shadow/orders qty=2"]);
+        assert_eq!(stats.mirrored(), 1);
+        assert_eq!(stats.dropped(), 0);
+    }
+
+    #[tokio::test]
+    async fn sampling_mirrors_a_deterministic_fraction() {
+        let addr = "127.0.0.1:48262";
+        upstream(addr, &["/sampled"]).await;
+
+        let mirror = Mirror::new(&format!("http://{}", addr), 16).sample_rate(0.5);
+        for _ in 0..10 {
+            let mut req = request("GET", "/sampled");
+            assert!(mirror.before(&mut req).is_none());
+        }
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert_eq!(captured_under("/sampled").len(), 5);
+    }
+
+    #[tokio::test]
+    async fn full_queue_drops_and_counts_instead_of_blocking() {
+        // an upstream that accepts but never answers wedges the sender
+        // task on its first job
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            loop {
+                if socket.read(&mut buf).await.unwrap_or(0) == 0 {
+                    return;
+                }
+            }
+        });
+
+        let mirror = Mirror::new(&format!("http://{}", addr), 1);
+        let stats = mirror.stats();
+
+        let started = Instant::now();
+        for _ in 0..3 {
+            let mut req = request("GET", "/stalled");
+            assert!(mirror.before(&mut req).is_none());
+        }
+        assert!(started.elapsed() < Duration::from_millis(100), "must not block");
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(stats.dropped() >= 1, "overflow must be counted");
+    }
+
+    #[tokio::test]
+    async fn method_and_route_filters_skip_non_matching_requests() {
+        let addr = "127.0.0.1:48263";
+        upstream(addr, &["/api/filtered"]).await;
+
+        let mirror = Mirror::new(&format!("http://{}", addr), 8)
+            .methods(vec!["POST"])
+            .routes(vec!["/api"]);
+        let stats = mirror.stats();
+
+        let mut req = request("GET", "/api/filtered");
+        assert!(mirror.before(&mut req).is_none());
+        let mut req = request("POST", "/elsewhere");
+        assert!(mirror.before(&mut req).is_none());
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(captured_under("/api/").is_empty());
+        assert_eq!(stats.mirrored(), 0);
+    }
+}
@@ -0,0 +1,99 @@
+//! Cross-cutting request/response hooks attached to a [`Router`].
+//!
+//! [`Router`]: crate::Router
+
+use crate::{Request, Response};
+
+mod cache;
+mod capture;
+mod common_log;
+mod cors;
+mod csrf;
+mod https_redirect;
+mod idempotency;
+mod ip_filter;
+mod jwt;
+mod maintenance;
+#[cfg(feature = "client")]
+mod mirror;
+
+pub use cache::Cache;
+pub use capture::Capture;
+pub use common_log::CommonLogWriter;
+pub use cors::Cors;
+pub use csrf::Csrf;
+pub use https_redirect::{HttpsRedirect, SCHEME_KEY};
+pub use idempotency::{BeginOutcome, IdempotencyKeys, IdempotencyStore, MemoryIdempotencyStore, StoredResponse};
+pub use ip_filter::IpFilter;
+pub use jwt::JwtAuth;
+pub use maintenance::Maintenance;
+#[cfg(feature = "client")]
+pub use mirror::{Mirror, MirrorStats};
+
+/// A hook that runs around every handler on the router it is attached to.
+///
+/// Middleware runs in registration order. A `before` hook may short-circuit
+/// by returning a response, in which case the handler (and any later
+/// middleware) never runs. `after` hooks run in the same order and may
+/// replace or decorate the outgoing response.
+pub trait Middleware: Send + Sync {
+    /// Runs before the route handler. Returning `Some(response)`
+    /// short-circuits the request.
+    fn before(&self, _req: &mut Request) -> Option<Response> {
+        None
+    }
+
+    /// Runs after the route handler with the outgoing response.
+    fn after(&self, _req: &Request, res: Response) -> Response {
+        res
+    }
+}
+
+/// [`Middleware`] from a plain pre-handler function; see
+/// [`Router::use_before_func`].
+///
+/// [`Router::use_before_func`]: crate::Router::use_before_func
+pub(crate) struct BeforeFunc(pub(crate) fn(&mut Request) -> Option<Response>);
+
+impl Middleware for BeforeFunc {
+    fn before(&self, req: &mut Request) -> Option<Response> {
+        (self.0)(req)
+    }
+}
+
+/// [`Middleware`] from a plain response-rewriting function; see
+/// [`Router::use_after_func`].
+///
+/// [`Router::use_after_func`]: crate::Router::use_after_func
+pub(crate) struct AfterFunc(pub(crate) fn(&Request, Response) -> Response);
+
+impl Middleware for AfterFunc {
+    fn after(&self, req: &Request, res: Response) -> Response {
+        (self.0)(req, res)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test_util {
+    use std::collections::HashMap;
+
+    use crate::Request;
+
+    /// Bare request for middleware unit tests.
+    pub(crate) fn request(method: &str, path: &str) -> Request {
+        Request {
+            path: path.to_owned(),
+            raw_path: path.to_owned(),
+            query: HashMap::new(),
+            raw_query: String::new(),
+            method: method.into(),
+            version: crate::HttpVersion::default(),
+            headers: crate::Headers::new(),
+            body: Vec::new(),
+            raw_body: crate::Body::default(),
+            params: HashMap::new(),
+            extensions: HashMap::new(),
+            remote_addr: None,
+        }
+    }
+}
@@ -0,0 +1,284 @@
+//! `multipart/form-data` body parsing (RFC 7578), as browsers and
+//! `curl -F` submit file uploads.
+
+use std::fmt::{self, Display};
+
+use crate::{Headers, Request, Response};
+
+/// One part of a `multipart/form-data` body.
+#[derive(Debug, Clone)]
+pub struct Part {
+    /// The `name` parameter of the part's `Content-Disposition`.
+    pub name: String,
+    /// The `filename` parameter, present for file fields.
+    pub filename: Option<String>,
+    /// The part's own headers.
+    pub headers: Headers,
+    /// Raw part bytes, exactly as sent.
+    pub data: Vec<u8>,
+}
+
+impl Part {
+    /// The part's `Content-Type`, when it declared one.
+    pub fn content_type(&self) -> Option<&str> {
+        self.headers.get("Content-Type")
+    }
+}
+
+/// Why a multipart body could not be parsed; `From<MultipartError>`
+/// renders the matching error response so handlers can bail with
+/// `err.into()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MultipartError {
+    /// `Content-Type` is missing or not `multipart/form-data` (415)
+    WrongContentType,
+    /// The `Content-Type` names no `boundary` parameter (400)
+    MissingBoundary,
+    /// The body does not follow the boundary framing, or a part is
+    /// missing its `Content-Disposition` name (400)
+    Malformed,
+}
+
+impl MultipartError {
+    /// Status code this error maps to.
+    pub fn status(&self) -> u16 {
+        match self {
+            MultipartError::WrongContentType => 415,
+            MultipartError::MissingBoundary | MultipartError::Malformed => 400,
+        }
+    }
+}
+
+impl Display for MultipartError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MultipartError::WrongContentType => {
+                write!(f, "expected multipart/form-data body")
+            }
+            MultipartError::MissingBoundary => {
+                write!(f, "multipart content type names no boundary")
+            }
+            MultipartError::Malformed => write!(f, "malformed multipart body"),
+        }
+    }
+}
+
+impl std::error::Error for MultipartError {}
+
+impl From<MultipartError> for Response {
+    fn from(err: MultipartError) -> Response {
+        Response::new(err.status(), err)
+    }
+}
+
+impl Request {
+    /// Parses a `multipart/form-data` body into its parts
+    ///
+    /// The boundary comes from the `Content-Type` header; preamble
+    /// before the first boundary and epilogue after the closing one
+    /// are ignored, and part bytes come through untouched, so binary
+    /// uploads survive exactly
+    ///
+    /// # Examples
+    /// ```
+    /// use http_server_starter_rust::{Request, Response};
+    ///
+    /// fn upload(req: &Request) -> Response {
+    ///     let parts = match req.multipart() {
+    ///         Ok(parts) => parts,
+    ///         Err(e) => return e.into(),
+    ///     };
+    ///     match parts.iter().find(|p| p.filename.is_some()) {
+    ///         Some(file) => Response::new(201, format!("{} bytes", file.data.len())),
+    ///         None => Response::new(400, "no file field"),
+    ///     }
+    /// }
+    /// ```
+    pub fn multipart(&self) -> Result<Vec<Part>, MultipartError> {
+        let content_type = self
+            .headers
+            .get("Content-Type")
+            .ok_or(MultipartError::WrongContentType)?;
+        let mime = content_type.split(';').next().unwrap_or(content_type).trim();
+        if !mime.eq_ignore_ascii_case("multipart/form-data") {
+            return Err(MultipartError::WrongContentType);
+        }
+
+        let boundary = param(content_type, "boundary").ok_or(MultipartError::MissingBoundary)?;
+        parse(&self.body, &boundary)
+    }
+}
+
+fn parse(body: &[u8], boundary: &str) -> Result<Vec<Part>, MultipartError> {
+    let first = format!("--{}", boundary).into_bytes();
+    // every later delimiter owns the CRLF that ends the previous part
+    let delimiter = format!("\r\n--{}", boundary).into_bytes();
+
+    let start = find(body, &first).ok_or(MultipartError::Malformed)?;
+    let mut rest = &body[start + first.len()..];
+    let mut parts = Vec::new();
+
+    loop {
+        // `--` closes the body; whatever follows is epilogue
+        if rest.starts_with(b"--") {
+            return Ok(parts);
+        }
+
+        // transport padding, then the CRLF ending the delimiter line
+        let eol = find(rest, b"\r\n").ok_or(MultipartError::Malformed)?;
+        if rest[..eol].iter().any(|b| !matches!(b, b' ' | b'\t')) {
+            return Err(MultipartError::Malformed);
+        }
+        rest = &rest[eol + 2..];
+
+        let head_end = find(rest, b"\r\n\r\n").ok_or(MultipartError::Malformed)?;
+        let head =
+            std::str::from_utf8(&rest[..head_end]).map_err(|_| MultipartError::Malformed)?;
+        rest = &rest[head_end + 4..];
+
+        let data_end = find(rest, &delimiter).ok_or(MultipartError::Malformed)?;
+        let data = rest[..data_end].to_vec();
+        rest = &rest[data_end + delimiter.len()..];
+
+        let mut headers = Headers::new();
+        for line in head.split("\r\n").filter(|l| !l.is_empty()) {
+            let (key, value) = line.split_once(':').ok_or(MultipartError::Malformed)?;
+            headers.insert(key.trim(), value.trim());
+        }
+        let disposition = headers
+            .get("Content-Disposition")
+            .ok_or(MultipartError::Malformed)?;
+        let name = param(disposition, "name").ok_or(MultipartError::Malformed)?;
+        let filename = param(disposition, "filename");
+
+        parts.push(Part {
+            name,
+            filename,
+            headers,
+            data,
+        });
+    }
+}
+
+/// A `key=value` or `key="value"` parameter from a header value like
+/// `multipart/form-data; boundary=x` or `form-data; name="field"`.
+fn param(value: &str, key: &str) -> Option<String> {
+    value.split(';').skip(1).find_map(|part| {
+        let (k, v) = part.trim().split_once('=')?;
+        k.eq_ignore_ascii_case(key)
+            .then(|| v.trim().trim_matches('"').to_owned())
+    })
+}
+
+/// Index of the first occurrence of `needle` in `haystack`.
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+
+    const BOUNDARY: &str = "------------------------d74496d66958873e";
+
+    fn multipart_request(content_type: &str, body: &[u8]) -> Request {
+        let mut req = request("POST", "/files/upload");
+        req.headers.insert("Content-Type", content_type);
+        req.body = body.to_vec();
+        req
+    }
+
+    /// A two-part body shaped the way `curl -F field=value -F
+    /// file=@logo.png` frames it, with CRLF and NUL bytes in the file.
+    fn curl_style_body() -> Vec<u8> {
+        let mut body = Vec::new();
+        body.extend_from_slice(
+            format!(
+                "--{b}\r\n\
+                 Content-Disposition: form-data; name=\"field\"\r\n\
+                 \r\n\
+                 value\r\n\
+                 --{b}\r\n\
+                 Content-Disposition: form-data; name=\"file\"; filename=\"logo.png\"\r\n\
+                 Content-Type: application/octet-stream\r\n\
+                 \r\n",
+                b = BOUNDARY
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&[0x89, b'P', b'N', b'G', b'\r', b'\n', 0x00, 0xff]);
+        body.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+        body
+    }
+
+    #[test]
+    fn curl_style_two_part_body_round_trips() {
+        let req = multipart_request(
+            &format!("multipart/form-data; boundary={}", BOUNDARY),
+            &curl_style_body(),
+        );
+
+        let parts = req.multipart().unwrap();
+        assert_eq!(parts.len(), 2);
+
+        assert_eq!(parts[0].name, "field");
+        assert_eq!(parts[0].filename, None);
+        assert_eq!(parts[0].data, b"value");
+
+        assert_eq!(parts[1].name, "file");
+        assert_eq!(parts[1].filename.as_deref(), Some("logo.png"));
+        assert_eq!(parts[1].content_type(), Some("application/octet-stream"));
+        assert_eq!(parts[1].data, [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x00, 0xff]);
+    }
+
+    #[test]
+    fn preamble_and_epilogue_are_ignored() {
+        let mut body = b"this preamble is to be ignored\r\n".to_vec();
+        body.extend_from_slice(&curl_style_body());
+        body.extend_from_slice(b"and this epilogue too");
+
+        let req = multipart_request(
+            &format!("multipart/form-data; boundary=\"{}\"", BOUNDARY),
+            &body,
+        );
+        assert_eq!(req.multipart().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn wrong_content_type_and_missing_boundary_are_typed() {
+        let req = multipart_request("application/x-www-form-urlencoded", b"a=1");
+        let err = req.multipart().unwrap_err();
+        assert_eq!(err, MultipartError::WrongContentType);
+        assert_eq!(Response::from(err).code(), 415);
+
+        let req = multipart_request("multipart/form-data", b"");
+        assert_eq!(req.multipart().unwrap_err(), MultipartError::MissingBoundary);
+    }
+
+    #[test]
+    fn broken_framing_is_malformed() {
+        let content_type = format!("multipart/form-data; boundary={}", BOUNDARY);
+
+        // no boundary in the body at all
+        let req = multipart_request(&content_type, b"no delimiters here");
+        assert_eq!(req.multipart().unwrap_err(), MultipartError::Malformed);
+
+        // a part that never terminates
+        let truncated = format!(
+            "--{b}\r\nContent-Disposition: form-data; name=\"x\"\r\n\r\ndangling",
+            b = BOUNDARY
+        );
+        let req = multipart_request(&content_type, truncated.as_bytes());
+        assert_eq!(req.multipart().unwrap_err(), MultipartError::Malformed);
+
+        // a part without a Content-Disposition name
+        let anonymous = format!(
+            "--{b}\r\nContent-Type: text/plain\r\n\r\nhi\r\n--{b}--\r\n",
+            b = BOUNDARY
+        );
+        let req = multipart_request(&content_type, anonymous.as_bytes());
+        assert_eq!(req.multipart().unwrap_err(), MultipartError::Malformed);
+    }
+}
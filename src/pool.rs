@@ -0,0 +1,103 @@
+//! Byte buffer reuse across connections.
+//!
+//! Every connection needs a read buffer and a scratch buffer for the
+//! response head; allocating them fresh per connection is pure allocator
+//! churn at high request rates. The pool hands out length-reset (never
+//! zeroed) buffers and takes them back on completion. Both the pooled
+//! buffer count and the retained capacity per buffer are capped so a
+//! burst of large requests does not pin memory forever.
+
+use std::sync::Mutex;
+
+/// Initial capacity of a freshly allocated buffer.
+pub(crate) const BUF_SIZE: usize = 4096;
+
+/// Most buffers retained at once; excess buffers are simply dropped.
+pub(crate) const MAX_POOLED: usize = 64;
+
+/// Buffers that grew beyond this capacity are dropped rather than pooled.
+pub(crate) const MAX_POOLED_CAPACITY: usize = 64 * 1024;
+
+pub(crate) struct BufferPool {
+    buffers: Mutex<Vec<Vec<u8>>>,
+    max_pooled: usize,
+    max_capacity: usize,
+}
+
+impl BufferPool {
+    pub(crate) fn new(max_pooled: usize, max_capacity: usize) -> BufferPool {
+        BufferPool {
+            buffers: Mutex::new(Vec::new()),
+            max_pooled,
+            max_capacity,
+        }
+    }
+
+    /// Checks out an empty buffer, reusing a pooled allocation if one is
+    /// available.
+    pub(crate) fn take(&self) -> Vec<u8> {
+        self.buffers
+            .lock()
+            .unwrap()
+            .pop()
+            .unwrap_or_else(|| Vec::with_capacity(BUF_SIZE))
+    }
+
+    /// Returns a buffer to the pool. The buffer is length-reset, not
+    /// zeroed; oversized or surplus buffers are dropped.
+    pub(crate) fn put(&self, mut buf: Vec<u8>) {
+        if buf.capacity() > self.max_capacity {
+            return;
+        }
+        buf.clear();
+
+        let mut buffers = self.buffers.lock().unwrap();
+        if buffers.len() < self.max_pooled {
+            buffers.push(buf);
+        }
+    }
+
+    #[cfg(test)]
+    fn pooled(&self) -> usize {
+        self.buffers.lock().unwrap().len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn pooled_count_never_exceeds_cap() {
+        let pool = BufferPool::new(4, MAX_POOLED_CAPACITY);
+
+        let buffers: Vec<Vec<u8>> = (0..8).map(|_| pool.take()).collect();
+        for buf in buffers {
+            pool.put(buf);
+        }
+
+        assert_eq!(pool.pooled(), 4);
+    }
+
+    #[test]
+    fn oversized_buffers_are_dropped() {
+        let pool = BufferPool::new(4, MAX_POOLED_CAPACITY);
+        pool.put(Vec::with_capacity(MAX_POOLED_CAPACITY * 2));
+        assert_eq!(pool.pooled(), 0);
+    }
+
+    #[test]
+    fn reused_buffers_come_back_empty_with_capacity() {
+        let pool = BufferPool::new(4, MAX_POOLED_CAPACITY);
+
+        let mut buf = pool.take();
+        buf.extend_from_slice(&[1; 1000]);
+        let capacity = buf.capacity();
+        pool.put(buf);
+
+        let buf = pool.take();
+        assert_eq!(buf.len(), 0);
+        assert_eq!(buf.capacity(), capacity);
+    }
+}
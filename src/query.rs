@@ -0,0 +1,336 @@
+//! Typed query string extraction (`serde` feature).
+//!
+//! Implements a small serde `Deserializer` directly over the parsed
+//! key-value pair list, so extraction does not round-trip through JSON.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+
+use serde::de::{self, DeserializeOwned, IntoDeserializer};
+
+use crate::Request;
+
+/// Error from [`Request::query_as`], naming the offending field where
+/// possible so routers can render a useful 400.
+#[derive(Debug, PartialEq, Eq)]
+pub struct QueryError {
+    message: String,
+}
+
+impl Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid query string: {}", self.message)
+    }
+}
+
+impl std::error::Error for QueryError {}
+
+impl de::Error for QueryError {
+    fn custom<T: Display>(msg: T) -> QueryError {
+        QueryError {
+            message: msg.to_string(),
+        }
+    }
+}
+
+impl Request {
+    /// Deserializes the query string into a typed struct
+    ///
+    /// Missing keys map to `Option::None`, repeated keys collect into a
+    /// `Vec`, and booleans accept `true`/`false`/`1`/`0`
+    ///
+    /// # Examples
+    /// ```
+    /// use http_server_starter_rust::{Request, Response};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Pagination {
+    ///     page: u32,
+    ///     per_page: Option<u32>,
+    /// }
+    ///
+    /// fn list(req: &Request) -> Response {
+    ///     match req.query_as::<Pagination>() {
+    ///         Ok(p) => Response::new(200, format!("page {}", p.page)),
+    ///         Err(e) => Response::new(400, e.to_string()),
+    ///     }
+    /// }
+    /// ```
+    pub fn query_as<T: DeserializeOwned>(&self) -> Result<T, QueryError> {
+        T::deserialize(QueryDeserializer {
+            groups: group_pairs(&self.raw_query),
+        })
+    }
+}
+
+/// Splits a raw query string into decoded pairs, grouping repeated keys
+/// in first-occurrence order.
+fn group_pairs(raw: &str) -> Vec<(String, Vec<String>)> {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+
+    for pair in raw.split('&').filter(|p| !p.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let key = decode(key);
+        let value = decode(value);
+
+        match index.get(&key) {
+            Some(&i) => groups[i].1.push(value),
+            None => {
+                index.insert(key.clone(), groups.len());
+                groups.push((key, vec![value]));
+            }
+        }
+    }
+    groups
+}
+
+/// Percent-decodes a query component, treating `+` as space.
+fn decode(s: &str) -> String {
+    crate::urlencoding::decode_lossy(&s.replace('+', " "))
+}
+
+struct QueryDeserializer {
+    groups: Vec<(String, Vec<String>)>,
+}
+
+impl<'de> de::Deserializer<'de> for QueryDeserializer {
+    type Error = QueryError;
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_map(GroupsAccess {
+            iter: self.groups.into_iter(),
+            pending: None,
+        })
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes
+        byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct GroupsAccess {
+    iter: std::vec::IntoIter<(String, Vec<String>)>,
+    pending: Option<(String, Vec<String>)>,
+}
+
+impl<'de> de::MapAccess<'de> for GroupsAccess {
+    type Error = QueryError;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, QueryError>
+    where
+        K: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some((key, values)) => {
+                let deserialized = seed.deserialize(key.as_str().into_deserializer())?;
+                self.pending = Some((key, values));
+                Ok(Some(deserialized))
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, QueryError>
+    where
+        V: de::DeserializeSeed<'de>,
+    {
+        let (key, values) = self.pending.take().expect("next_value before next_key");
+        seed.deserialize(ValueDeserializer {
+            key: &key,
+            values: &values,
+        })
+    }
+}
+
+struct ValueDeserializer<'a> {
+    key: &'a str,
+    values: &'a [String],
+}
+
+impl<'a> ValueDeserializer<'a> {
+    fn first(&self) -> &'a str {
+        self.values.first().map(String::as_str).unwrap_or("")
+    }
+
+    fn parse<T: std::str::FromStr>(&self, expected: &str) -> Result<T, QueryError> {
+        self.first().parse().map_err(|_| QueryError {
+            message: format!(
+                "invalid value `{}` for `{}`: expected {}",
+                self.first(),
+                self.key,
+                expected
+            ),
+        })
+    }
+}
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident as $ty:ty,)*) => {
+        $(
+            fn $method<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+                visitor.$visit(self.parse::<$ty>(stringify!($ty))?)
+            }
+        )*
+    };
+}
+
+impl<'de, 'a> de::Deserializer<'de> for ValueDeserializer<'a> {
+    type Error = QueryError;
+
+    deserialize_parsed! {
+        deserialize_i8 => visit_i8 as i8,
+        deserialize_i16 => visit_i16 as i16,
+        deserialize_i32 => visit_i32 as i32,
+        deserialize_i64 => visit_i64 as i64,
+        deserialize_u8 => visit_u8 as u8,
+        deserialize_u16 => visit_u16 as u16,
+        deserialize_u32 => visit_u32 as u32,
+        deserialize_u64 => visit_u64 as u64,
+        deserialize_f32 => visit_f32 as f32,
+        deserialize_f64 => visit_f64 as f64,
+    }
+
+    fn deserialize_bool<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        match self.first() {
+            "true" | "1" => visitor.visit_bool(true),
+            "false" | "0" => visitor.visit_bool(false),
+            other => Err(QueryError {
+                message: format!("invalid value `{}` for `{}`: expected bool", other, self.key),
+            }),
+        }
+    }
+
+    fn deserialize_option<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_seq<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_seq(ValuesAccess {
+            key: self.key,
+            iter: self.values.iter(),
+        })
+    }
+
+    fn deserialize_any<V: de::Visitor<'de>>(self, visitor: V) -> Result<V::Value, QueryError> {
+        visitor.visit_str(self.first())
+    }
+
+    serde::forward_to_deserialize_any! {
+        char str string bytes byte_buf unit unit_struct newtype_struct
+        tuple tuple_struct map struct enum identifier ignored_any
+    }
+}
+
+struct ValuesAccess<'a> {
+    key: &'a str,
+    iter: std::slice::Iter<'a, String>,
+}
+
+impl<'de, 'a> de::SeqAccess<'de> for ValuesAccess<'a> {
+    type Error = QueryError;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, QueryError>
+    where
+        T: de::DeserializeSeed<'de>,
+    {
+        match self.iter.next() {
+            Some(value) => seed
+                .deserialize(ValueDeserializer {
+                    key: self.key,
+                    values: std::slice::from_ref(value),
+                })
+                .map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use serde::Deserialize;
+
+    // through the real parser, so these fail if extraction ever reads
+    // a field the wire path does not populate (the query lives in
+    // `raw_query`, not `path`)
+    fn get(target: &str) -> Request {
+        let raw = format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", target);
+        Request::from_utf8(raw.as_bytes()).unwrap()
+    }
+
+    #[derive(Debug, PartialEq, Deserialize)]
+    struct Pagination {
+        page: u32,
+        per_page: Option<u32>,
+    }
+
+    #[test]
+    fn basic_struct() {
+        let req = get("/items?page=2&per_page=50");
+        assert_eq!(
+            req.query_as::<Pagination>().unwrap(),
+            Pagination {
+                page: 2,
+                per_page: Some(50)
+            }
+        );
+    }
+
+    #[test]
+    fn option_defaults_to_none() {
+        let req = get("/items?page=1");
+        assert_eq!(
+            req.query_as::<Pagination>().unwrap(),
+            Pagination {
+                page: 1,
+                per_page: None
+            }
+        );
+    }
+
+    #[test]
+    fn missing_required_field_names_it() {
+        let req = get("/items?per_page=50");
+        let err = req.query_as::<Pagination>().unwrap_err();
+        assert!(err.to_string().contains("page"), "{}", err);
+    }
+
+    #[test]
+    fn repeated_keys_into_vec() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Filter {
+            tag: Vec<String>,
+        }
+
+        let req = get("/items?tag=a&tag=b%20c&tag=d");
+        assert_eq!(
+            req.query_as::<Filter>().unwrap().tag,
+            vec!["a", "b c", "d"]
+        );
+    }
+
+    #[test]
+    fn type_mismatch_names_field() {
+        let req = get("/items?page=abc");
+        let err = req.query_as::<Pagination>().unwrap_err();
+        assert!(err.to_string().contains("page"), "{}", err);
+        assert!(err.to_string().contains("abc"), "{}", err);
+    }
+
+    #[test]
+    fn booleans_from_numeric_forms() {
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Flags {
+            a: bool,
+            b: bool,
+        }
+
+        let req = get("/items?a=1&b=false");
+        assert_eq!(req.query_as::<Flags>().unwrap(), Flags { a: true, b: false });
+    }
+}
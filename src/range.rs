@@ -0,0 +1,406 @@
+//! RFC 7233 byte-range responses from a seekable source.
+//!
+//! [`respond`] inspects the request's `Range` header and answers with
+//! the full body, a simple single-range 206, or a
+//! `multipart/byteranges` 206 when several ranges were asked for. The
+//! multipart envelope is built up front so `Content-Length` is exact
+//! and keep-alive framing keeps working.
+
+use std::io::{self, Read, Seek, SeekFrom};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::{httpdate, Request, Response};
+
+/// Validators describing the entity being served, consulted for
+/// `If-Range` and echoed on the response.
+#[derive(Default)]
+pub struct Validators<'a> {
+    /// Strong entity tag, quotes included (`"abc123"`).
+    pub etag: Option<&'a str>,
+    pub last_modified: Option<SystemTime>,
+}
+
+/// Responds to `req` with the requested byte ranges of `source`.
+///
+/// Without a `Range` header the full content is served with a 200;
+/// unsatisfiable ranges get a 416 with the total length.
+pub fn respond<S: Read + Seek>(
+    req: &Request,
+    source: &mut S,
+    content_type: &str,
+) -> io::Result<Response> {
+    respond_conditional(req, source, content_type, &Validators::default())
+}
+
+/// Like [`respond`] but honoring `If-Range`: when the header is present
+/// and does not match `validators`, the `Range` is ignored and the full
+/// entity is served, which is what resuming download managers expect.
+pub fn respond_conditional<S: Read + Seek>(
+    req: &Request,
+    source: &mut S,
+    content_type: &str,
+    validators: &Validators,
+) -> io::Result<Response> {
+    let boundary = crate::crypto::base64url_encode(&crate::crypto::random_bytes(12));
+    build(req, source, content_type, &boundary, validators)
+}
+
+/// Like [`respond`] but with a caller-chosen multipart boundary, so
+/// wire-format tests can be deterministic.
+pub fn respond_with_boundary<S: Read + Seek>(
+    req: &Request,
+    source: &mut S,
+    content_type: &str,
+    boundary: &str,
+) -> io::Result<Response> {
+    build(req, source, content_type, boundary, &Validators::default())
+}
+
+fn build<S: Read + Seek>(
+    req: &Request,
+    source: &mut S,
+    content_type: &str,
+    boundary: &str,
+    validators: &Validators,
+) -> io::Result<Response> {
+    let total = source.seek(SeekFrom::End(0))?;
+
+    let header = match req.headers.get("Range") {
+        Some(header) if if_range_matches(req, validators) => header,
+        _ => {
+            let body = read_range(source, 0, total)?;
+            let res = Response::bytes(200, body)
+                .add_header("Content-Type", content_type)
+                .add_header("Accept-Ranges", "bytes");
+            return Ok(with_validators(res, validators));
+        }
+    };
+
+    let ranges = match parse_ranges(header, total) {
+        Some(ranges) if !ranges.is_empty() => ranges,
+        _ => {
+            return Ok(Response::new(416, "range not satisfiable")
+                .add_header("Content-Range", &format!("bytes */{}", total)))
+        }
+    };
+
+    if let [(start, end)] = ranges[..] {
+        let body = read_range(source, start, end - start + 1)?;
+        let res = Response::bytes(206, body)
+            .add_header("Content-Type", content_type)
+            .add_header("Content-Range", &format!("bytes {}-{}/{}", start, end, total))
+            .add_header("Accept-Ranges", "bytes");
+        return Ok(with_validators(res, validators));
+    }
+
+    let mut body = Vec::new();
+    for (start, end) in ranges {
+        body.extend_from_slice(
+            format!(
+                "--{}\r\nContent-Type: {}\r\nContent-Range: bytes {}-{}/{}\r\n\r\n",
+                boundary, content_type, start, end, total
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&read_range(source, start, end - start + 1)?);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{}--\r\n", boundary).as_bytes());
+
+    let res = Response::bytes(206, body)
+        .add_header(
+            "Content-Type",
+            &format!("multipart/byteranges; boundary={}", boundary),
+        )
+        .add_header("Accept-Ranges", "bytes");
+    Ok(with_validators(res, validators))
+}
+
+/// Whether a `Range` header should be honored given `If-Range`.
+///
+/// Absent `If-Range` always honors the range. ETag comparison is strong
+/// only, so a weak validator never matches; dates match on exact
+/// second equality per RFC 7233.
+fn if_range_matches(req: &Request, validators: &Validators) -> bool {
+    let value = match req.headers.get("If-Range") {
+        Some(value) => value.trim(),
+        None => return true,
+    };
+
+    if value.starts_with("W/") {
+        return false;
+    }
+    if value.starts_with('"') {
+        return validators.etag == Some(value);
+    }
+
+    match (httpdate::parse_http_date(value), validators.last_modified) {
+        (Some(sent), Some(modified)) => {
+            let seconds = |t: SystemTime| {
+                t.duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_secs())
+                    .unwrap_or(0)
+            };
+            seconds(sent) == seconds(modified)
+        }
+        _ => false,
+    }
+}
+
+/// Echoes the entity validators on the response so clients can resume
+/// with `If-Range` later.
+fn with_validators(mut res: Response, validators: &Validators) -> Response {
+    if let Some(etag) = validators.etag {
+        res.add_headers("ETag", etag);
+    }
+    if let Some(modified) = validators.last_modified {
+        res.add_headers("Last-Modified", &httpdate::fmt_http_date(modified));
+    }
+    res
+}
+
+/// Parses `bytes=...` into inclusive (start, end) pairs in request
+/// order. Individually unsatisfiable ranges are dropped; a malformed
+/// spec returns None.
+fn parse_ranges(header: &str, total: u64) -> Option<Vec<(u64, u64)>> {
+    let spec = header.strip_prefix("bytes=")?;
+    let mut ranges = Vec::new();
+
+    for part in spec.split(',') {
+        let (start, end) = part.trim().split_once('-')?;
+
+        let range = match (start.is_empty(), end.is_empty()) {
+            // suffix form: the last N bytes
+            (true, false) => {
+                let n: u64 = end.parse().ok()?;
+                if n == 0 {
+                    continue;
+                }
+                (total.saturating_sub(n), total.saturating_sub(1))
+            }
+            // open-ended: from start to the end
+            (false, true) => (start.parse().ok()?, total.saturating_sub(1)),
+            (false, false) => {
+                let end: u64 = end.parse().ok()?;
+                (start.parse().ok()?, end.min(total.saturating_sub(1)))
+            }
+            (true, true) => return None,
+        };
+
+        if range.0 >= total || range.0 > range.1 {
+            continue;
+        }
+        ranges.push(range);
+    }
+
+    Some(ranges)
+}
+
+fn read_range<S: Read + Seek>(source: &mut S, start: u64, len: u64) -> io::Result<Vec<u8>> {
+    source.seek(SeekFrom::Start(start))?;
+    let mut body = Vec::new();
+    source.take(len).read_to_end(&mut body)?;
+    Ok(body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+    use std::io::Cursor;
+
+    const ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz";
+
+    fn ranged(range: Option<&str>) -> Request {
+        let mut req = request("GET", "/file");
+        if let Some(range) = range {
+            req.headers.insert("Range", range);
+        }
+        req
+    }
+
+    fn body_of(res: &Response) -> String {
+        res.data.as_ref().map(|d| d.text()).unwrap_or_default()
+    }
+
+    #[test]
+    fn no_range_serves_everything() {
+        let res =
+            respond(&ranged(None), &mut Cursor::new(ALPHABET), "text/plain").unwrap();
+        assert_eq!(res.code, 200);
+        assert_eq!(body_of(&res), ALPHABET);
+        assert_eq!(res.headers.get("Accept-Ranges").unwrap(), "bytes");
+    }
+
+    #[test]
+    fn single_range_takes_the_simple_206_path() {
+        let res = respond(
+            &ranged(Some("bytes=0-4")),
+            &mut Cursor::new(ALPHABET),
+            "text/plain",
+        )
+        .unwrap();
+
+        assert_eq!(res.code, 206);
+        assert_eq!(body_of(&res), "abcde");
+        assert_eq!(res.headers.get("Content-Range").unwrap(), "bytes 0-4/26");
+        assert_eq!(res.headers.get("Content-Type").unwrap(), "text/plain");
+    }
+
+    #[test]
+    fn multiple_ranges_build_a_multipart_envelope() {
+        let res = respond_with_boundary(
+            &ranged(Some("bytes=0-4,10-14")),
+            &mut Cursor::new(ALPHABET),
+            "text/plain",
+            "B0UNDARY",
+        )
+        .unwrap();
+
+        assert_eq!(res.code, 206);
+        assert_eq!(
+            res.headers.get("Content-Type").unwrap(),
+            "multipart/byteranges; boundary=B0UNDARY"
+        );
+
+        let body = body_of(&res);
+        assert_eq!(
+            body,
+            "--B0UNDARY\r\n\
+             Content-Type: text/plain\r\n\
+             Content-Range: bytes 0-4/26\r\n\
+             \r\n\
+             abcde\r\n\
+             --B0UNDARY\r\n\
+             Content-Type: text/plain\r\n\
+             Content-Range: bytes 10-14/26\r\n\
+             \r\n\
+             klmno\r\n\
+             --B0UNDARY--\r\n"
+        );
+        assert_eq!(
+            res.headers.get("Content-Length").unwrap(),
+            &body.len().to_string()
+        );
+    }
+
+    #[test]
+    fn part_order_follows_the_request() {
+        let res = respond_with_boundary(
+            &ranged(Some("bytes=20-25,0-2")),
+            &mut Cursor::new(ALPHABET),
+            "text/plain",
+            "B",
+        )
+        .unwrap();
+
+        let body = body_of(&res);
+        let first = body.find("uvwxyz").unwrap();
+        let second = body.find("abc").unwrap();
+        assert!(first < second, "{}", body);
+    }
+
+    #[test]
+    fn suffix_and_open_ended_forms() {
+        let res = respond(
+            &ranged(Some("bytes=-3")),
+            &mut Cursor::new(ALPHABET),
+            "text/plain",
+        )
+        .unwrap();
+        assert_eq!(body_of(&res), "xyz");
+        assert_eq!(res.headers.get("Content-Range").unwrap(), "bytes 23-25/26");
+
+        let res = respond(
+            &ranged(Some("bytes=24-")),
+            &mut Cursor::new(ALPHABET),
+            "text/plain",
+        )
+        .unwrap();
+        assert_eq!(body_of(&res), "yz");
+    }
+
+    fn validators() -> Validators<'static> {
+        Validators {
+            etag: Some("\"v1\""),
+            last_modified: httpdate::parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+        }
+    }
+
+    #[test]
+    fn matching_if_range_keeps_the_206() {
+        let mut req = ranged(Some("bytes=0-4"));
+        req.headers.insert("If-Range", "\"v1\"");
+
+        let res =
+            respond_conditional(&req, &mut Cursor::new(ALPHABET), "text/plain", &validators())
+                .unwrap();
+        assert_eq!(res.code, 206);
+        assert_eq!(body_of(&res), "abcde");
+        assert_eq!(res.headers.get("ETag").unwrap(), "\"v1\"");
+    }
+
+    #[test]
+    fn stale_if_range_serves_the_full_entity() {
+        let mut req = ranged(Some("bytes=0-4"));
+        req.headers.insert("If-Range", "\"v0\"");
+
+        let res =
+            respond_conditional(&req, &mut Cursor::new(ALPHABET), "text/plain", &validators())
+                .unwrap();
+        assert_eq!(res.code, 200);
+        assert_eq!(body_of(&res), ALPHABET);
+    }
+
+    #[test]
+    fn if_range_by_date() {
+        let mut req = ranged(Some("bytes=0-4"));
+        req.headers
+            .insert("If-Range", "Sun, 06 Nov 1994 08:49:37 GMT");
+        let res =
+            respond_conditional(&req, &mut Cursor::new(ALPHABET), "text/plain", &validators())
+                .unwrap();
+        assert_eq!(res.code, 206);
+
+        let mut req = ranged(Some("bytes=0-4"));
+        req.headers
+            .insert("If-Range", "Mon, 07 Nov 1994 08:49:37 GMT");
+        let res =
+            respond_conditional(&req, &mut Cursor::new(ALPHABET), "text/plain", &validators())
+                .unwrap();
+        assert_eq!(res.code, 200);
+    }
+
+    #[test]
+    fn weak_etags_never_match() {
+        let mut req = ranged(Some("bytes=0-4"));
+        req.headers.insert("If-Range", "W/\"v1\"");
+
+        let res =
+            respond_conditional(&req, &mut Cursor::new(ALPHABET), "text/plain", &validators())
+                .unwrap();
+        assert_eq!(res.code, 200);
+    }
+
+    #[test]
+    fn absent_if_range_keeps_current_behavior() {
+        let req = ranged(Some("bytes=0-4"));
+        let res =
+            respond_conditional(&req, &mut Cursor::new(ALPHABET), "text/plain", &validators())
+                .unwrap();
+        assert_eq!(res.code, 206);
+    }
+
+    #[test]
+    fn unsatisfiable_ranges_get_a_416() {
+        let res = respond(
+            &ranged(Some("bytes=100-200")),
+            &mut Cursor::new(ALPHABET),
+            "text/plain",
+        )
+        .unwrap();
+        assert_eq!(res.code, 416);
+        assert_eq!(res.headers.get("Content-Range").unwrap(), "bytes */26");
+    }
+}
@@ -0,0 +1,199 @@
+//! Files-on-disk serving behind [`Router::serve_dir`].
+//!
+//! [`Router::serve_dir`]: crate::Router::serve_dir
+
+use std::path::{Component, Path};
+
+use crate::{range, Request, Response};
+
+/// `Content-Type` guessed from the file extension; anything
+/// unrecognized goes out as `application/octet-stream`.
+pub(crate) fn content_type(path: &Path) -> &'static str {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+
+    match ext.as_deref() {
+        Some("html") | Some("htm") => "text/html",
+        Some("css") => "text/css",
+        Some("js") => "text/javascript",
+        Some("json") => "application/json",
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        Some("gif") => "image/gif",
+        Some("svg") => "image/svg+xml",
+        Some("txt") => "text/plain",
+        Some("ico") => "image/x-icon",
+        Some("pdf") => "application/pdf",
+        Some("wasm") => "application/wasm",
+        _ => "application/octet-stream",
+    }
+}
+
+/// Serves `rel` (the request path with the route prefix stripped) out
+/// of `root`: 200 with the file bytes, 404 when it does not exist, 403
+/// when the path tries to escape the root.
+///
+/// Directory requests fall back to their `index.html`. Byte ranges are
+/// honored through [`range::respond`], so full responses advertise
+/// `Accept-Ranges: bytes` and a `Range` header gets a 206 (or a 416
+/// when it lies outside the file). Responses also carry `ETag` and
+/// `Last-Modified` validators derived from the file's size and mtime,
+/// which the router's conditional-request handling turns into 304s.
+pub(crate) fn respond(req: &Request, root: &Path, rel: &str) -> Response {
+    // lexically first: a `..` component is a traversal attempt whether
+    // or not its target exists
+    let rel = Path::new(rel);
+    if rel
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Response::new(403, "forbidden");
+    }
+
+    let mut path = root.join(rel);
+    if path.is_dir() {
+        path.push("index.html");
+    }
+
+    // canonicalized so a symlink cannot escape the root either
+    let (resolved, root) = match (path.canonicalize(), root.canonicalize()) {
+        (Ok(resolved), Ok(root)) => (resolved, root),
+        _ => return Response::new(404, "file not found"),
+    };
+    if !resolved.starts_with(&root) {
+        return Response::new(403, "forbidden");
+    }
+
+    let mut file = match std::fs::File::open(&resolved) {
+        Ok(file) => file,
+        Err(_) => return Response::new(404, "file not found"),
+    };
+
+    // size + mtime make a cheap validator that changes whenever the
+    // file does, without hashing its contents
+    let metadata = file.metadata().ok();
+    let modified = metadata.as_ref().and_then(|m| m.modified().ok());
+    let etag = metadata.as_ref().map(|m| {
+        let mtime = modified
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs())
+            .unwrap_or(0);
+        format!("\"{:x}-{:x}\"", m.len(), mtime)
+    });
+    let validators = range::Validators {
+        etag: etag.as_deref(),
+        last_modified: modified,
+    };
+
+    match range::respond_conditional(req, &mut file, content_type(&resolved), &validators) {
+        Ok(res) => res,
+        Err(_) => Response::new(404, "file not found"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::test_util::request;
+    use pretty_assertions::assert_eq;
+
+    fn get() -> Request {
+        request("GET", "/files")
+    }
+
+    /// Scratch directory seeded with (name, contents) files; removed
+    /// on drop.
+    struct Root(std::path::PathBuf);
+
+    impl Root {
+        fn new(name: &str, files: &[(&str, &[u8])]) -> Root {
+            let dir = std::env::temp_dir().join(format!("static-{}-{}", std::process::id(), name));
+            std::fs::create_dir_all(&dir).unwrap();
+            for (name, contents) in files {
+                let path = dir.join(name);
+                std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+                std::fs::write(path, contents).unwrap();
+            }
+            Root(dir)
+        }
+    }
+
+    impl Drop for Root {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn known_extensions_map_and_unknown_fall_back() {
+        assert_eq!(content_type(Path::new("a/index.html")), "text/html");
+        assert_eq!(content_type(Path::new("style.CSS")), "text/css");
+        assert_eq!(content_type(Path::new("photo.jpeg")), "image/jpeg");
+        assert_eq!(content_type(Path::new("notes.txt")), "text/plain");
+        assert_eq!(content_type(Path::new("blob.bin")), "application/octet-stream");
+        assert_eq!(content_type(Path::new("Makefile")), "application/octet-stream");
+    }
+
+    #[test]
+    fn serves_binary_bytes_with_guessed_type() {
+        let png = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', 0x00, 0xff];
+        let root = Root::new("binary", &[("logo.png", &png)]);
+
+        let res = respond(&get(), &root.0, "logo.png");
+        assert_eq!(res.code(), 200);
+        assert_eq!(&res.headers["Content-Type"], "image/png");
+        assert_eq!(&res.headers["Content-Length"], png.len().to_string());
+        assert_eq!(res.data.unwrap().to_bytes(), png);
+    }
+
+    #[test]
+    fn range_requests_slice_binary_files() {
+        let png = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', 0x00, 0xff];
+        let root = Root::new("ranged", &[("logo.png", &png)]);
+
+        // a full response advertises that ranges are supported and
+        // carries validators for conditional re-fetches
+        let full = respond(&get(), &root.0, "logo.png");
+        assert_eq!(&full.headers["Accept-Ranges"], "bytes");
+        assert!(full.headers.contains_key("ETag"));
+        assert!(full.headers.contains_key("Last-Modified"));
+
+        let mut req = get();
+        req.headers.insert("Range", "bytes=0-3");
+        let res = respond(&req, &root.0, "logo.png");
+        assert_eq!(res.code(), 206);
+        assert_eq!(&res.headers["Content-Range"], "bytes 0-3/10");
+        assert_eq!(res.data.unwrap().to_bytes(), png[..4]);
+
+        let mut req = get();
+        req.headers.insert("Range", "bytes=100-");
+        assert_eq!(respond(&req, &root.0, "logo.png").code(), 416);
+    }
+
+    #[test]
+    fn dot_dot_is_rejected_and_missing_files_are_404() {
+        let root = Root::new("traversal", &[("ok.txt", b"fine")]);
+
+        assert_eq!(respond(&get(), &root.0, "../../etc/passwd").code(), 403);
+        assert_eq!(respond(&get(), &root.0, "a/../../escape.txt").code(), 403);
+        assert_eq!(respond(&get(), &root.0, "missing.txt").code(), 404);
+        assert_eq!(respond(&get(), &root.0, "ok.txt").code(), 200);
+    }
+
+    #[test]
+    fn directory_requests_serve_index_html() {
+        let root = Root::new(
+            "index",
+            &[("index.html", b"<h1>root</h1>"), ("docs/index.html", b"<h1>docs</h1>")],
+        );
+
+        let res = respond(&get(), &root.0, "");
+        assert_eq!(res.code(), 200);
+        assert_eq!(&res.headers["Content-Type"], "text/html");
+        assert_eq!(res.data.unwrap().text(), "<h1>root</h1>");
+
+        assert_eq!(respond(&get(), &root.0, "docs").data.unwrap().text(), "<h1>docs</h1>");
+    }
+}
@@ -0,0 +1,186 @@
+//! Request lifecycle tracing hooks for telemetry integration.
+
+use std::net::SocketAddr;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::Request;
+
+/// Shared per-request context handed to every [`Tracer`] callback.
+pub struct TraceContext {
+    /// Peer address of the connection
+    pub remote_addr: Option<SocketAddr>,
+    /// When the connection was accepted
+    pub accepted_at: Instant,
+}
+
+impl TraceContext {
+    pub(crate) fn new(remote_addr: Option<SocketAddr>) -> TraceContext {
+        TraceContext {
+            remote_addr,
+            accepted_at: Instant::now(),
+        }
+    }
+
+    /// Time elapsed since the connection was accepted.
+    pub fn elapsed(&self) -> Duration {
+        self.accepted_at.elapsed()
+    }
+}
+
+/// Fine-grained callbacks over the life of a request.
+///
+/// All methods default to no-ops, so implementations only override the
+/// events they care about. Callbacks are panic-isolated from the request
+/// path: a panicking tracer never affects the response.
+///
+/// # Examples
+/// ```
+/// use http_server_starter_rust::{Router, trace::{TraceContext, Tracer}};
+///
+/// struct StatusLog;
+///
+/// impl Tracer for StatusLog {
+///     fn handler_finished(&self, ctx: &TraceContext, status: u16) {
+///         println!("{} after {:?}", status, ctx.elapsed());
+///     }
+/// }
+///
+/// let mut r = Router::new("127.0.0.1:12345");
+/// r.set_tracer(StatusLog);
+/// ```
+#[allow(unused_variables)]
+pub trait Tracer: Send + Sync {
+    /// A connection was accepted.
+    fn connection_accepted(&self, ctx: &TraceContext) {}
+
+    /// The request head was parsed successfully.
+    fn head_parsed(&self, ctx: &TraceContext, req: &Request) {}
+
+    /// Route matching finished; `pattern` is the matched route's
+    /// registered path, or None when no route matched.
+    fn route_matched(&self, ctx: &TraceContext, pattern: Option<&str>) {}
+
+    /// The handler (or a short-circuiting middleware) is about to run.
+    fn handler_started(&self, ctx: &TraceContext) {}
+
+    /// The handler produced a response with the given status.
+    fn handler_finished(&self, ctx: &TraceContext, status: u16) {}
+
+    /// The response was fully written and flushed.
+    fn response_written(&self, ctx: &TraceContext) {}
+
+    /// A deferred [`after_send`] hook panicked; `message` is the panic
+    /// payload when it was a string.
+    ///
+    /// [`after_send`]: crate::Response::after_send
+    fn after_send_panicked(&self, ctx: &TraceContext, message: &str) {}
+
+    /// The connection was handed to an upgrade callback and is no
+    /// longer managed by the router; see [`crate::Response::upgrade`].
+    fn connection_upgraded(&self, ctx: &TraceContext) {}
+
+    /// The connection is closing.
+    fn connection_closed(&self, ctx: &TraceContext) {}
+}
+
+/// Invokes a tracer callback, swallowing panics so tracing can never
+/// take down the request path.
+pub(crate) fn emit(tracer: &Option<Arc<dyn Tracer>>, f: impl FnOnce(&dyn Tracer)) {
+    if let Some(tracer) = tracer {
+        let _ = catch_unwind(AssertUnwindSafe(|| f(tracer.as_ref())));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Request, Response, Router};
+    use pretty_assertions::assert_eq;
+    use std::sync::Mutex;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    #[derive(Clone, Default)]
+    struct RecordingTracer {
+        events: Arc<Mutex<Vec<(String, Duration)>>>,
+    }
+
+    impl RecordingTracer {
+        fn record(&self, ctx: &TraceContext, event: &str) {
+            self.events
+                .lock()
+                .unwrap()
+                .push((event.to_owned(), ctx.elapsed()));
+        }
+    }
+
+    impl Tracer for RecordingTracer {
+        fn connection_accepted(&self, ctx: &TraceContext) {
+            self.record(ctx, "connection_accepted");
+        }
+        fn head_parsed(&self, ctx: &TraceContext, req: &Request) {
+            self.record(ctx, "head_parsed");
+            assert_eq!(req.path, "/hello");
+        }
+        fn route_matched(&self, ctx: &TraceContext, pattern: Option<&str>) {
+            self.record(ctx, "route_matched");
+            assert_eq!(pattern, Some("/hello"));
+        }
+        fn handler_started(&self, ctx: &TraceContext) {
+            self.record(ctx, "handler_started");
+        }
+        fn handler_finished(&self, ctx: &TraceContext, status: u16) {
+            self.record(ctx, "handler_finished");
+            assert_eq!(status, 200);
+            // panics here must not affect the response
+            panic!("tracing must be panic-isolated");
+        }
+        fn response_written(&self, ctx: &TraceContext) {
+            self.record(ctx, "response_written");
+        }
+        fn connection_closed(&self, ctx: &TraceContext) {
+            self.record(ctx, "connection_closed");
+        }
+    }
+
+    #[tokio::test]
+    async fn records_lifecycle_events_in_order() {
+        let addr = "127.0.0.1:48251";
+        let tracer = RecordingTracer::default();
+
+        let mut r = Router::new(addr);
+        r.handle_func("/hello", |_req| Response::new(200, "hi"), vec!["GET"]);
+        r.set_tracer(tracer.clone());
+        tokio::spawn(async move { r.serve().await });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let mut socket = tokio::net::TcpStream::connect(addr).await.unwrap();
+        socket
+            .write_all(b"GET /hello HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\r\n")
+            .await
+            .unwrap();
+        let mut response = String::new();
+        socket.read_to_string(&mut response).await.unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let events = tracer.events.lock().unwrap();
+        let names: Vec<&str> = events.iter().map(|(name, _)| name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "connection_accepted",
+                "head_parsed",
+                "route_matched",
+                "handler_started",
+                "handler_finished",
+                "response_written",
+                "connection_closed",
+            ]
+        );
+
+        let durations: Vec<Duration> = events.iter().map(|(_, d)| *d).collect();
+        assert!(durations.windows(2).all(|w| w[0] <= w[1]));
+    }
+}
@@ -0,0 +1,229 @@
+//! Percent-encoding utilities.
+//!
+//! Path segments and query components have different reserved character
+//! sets; using the wrong one produces URLs that round-trip incorrectly
+//! through proxies and browsers, so each context gets its own encoder.
+
+/// Percent-encodes a single path segment.
+///
+/// Keeps unreserved characters and the sub-delims valid inside a path
+/// segment; everything else (including `/`) is encoded, so a segment can
+/// safely contain user-supplied text.
+///
+/// # Examples
+/// ```
+/// use http_server_starter_rust::urlencoding::encode_path_segment;
+///
+/// assert_eq!(encode_path_segment("a/b c"), "a%2Fb%20c");
+/// ```
+pub fn encode_path_segment(s: &str) -> String {
+    encode(s, |b| {
+        is_unreserved(b) || matches!(b, b'!' | b'$' | b'&' | b'\'' | b'(' | b')' | b'*' | b'+' | b',' | b';' | b'=' | b':' | b'@')
+    })
+}
+
+/// Percent-encodes a query component (a key or value).
+///
+/// Encodes `&`, `=`, `+` and `#` so the component cannot break the
+/// structure of the query string; spaces become `%20`.
+///
+/// # Examples
+/// ```
+/// use http_server_starter_rust::urlencoding::encode_query_component;
+///
+/// assert_eq!(encode_query_component("a=b&c"), "a%3Db%26c");
+/// ```
+pub fn encode_query_component(s: &str) -> String {
+    encode(s, |b| {
+        is_unreserved(b) || matches!(b, b'!' | b'$' | b'\'' | b'(' | b')' | b'*' | b',' | b';' | b':' | b'@' | b'/' | b'?')
+    })
+}
+
+/// Percent-decodes a string strictly: returns None on an invalid escape
+/// sequence or invalid UTF-8.
+///
+/// # Examples
+/// ```
+/// use http_server_starter_rust::urlencoding::decode;
+///
+/// assert_eq!(decode("a%20b").as_deref(), Some("a b"));
+/// assert_eq!(decode("a%2"), None);
+/// ```
+pub fn decode(s: &str) -> Option<String> {
+    String::from_utf8(decode_bytes(s)?).ok()
+}
+
+/// Percent-decodes a string leniently: invalid escapes are kept
+/// literally and invalid UTF-8 is replaced.
+pub fn decode_lossy(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match unescape_at(bytes, i) {
+            Some(b) => {
+                out.push(b);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
+
+/// Assembles a query string like `?a=1&b=x%20y` from pairs.
+///
+/// # Examples
+/// ```
+/// use http_server_starter_rust::urlencoding::QueryStringBuilder;
+///
+/// let qs = QueryStringBuilder::new()
+///     .append("a", "1")
+///     .append("b", "x y")
+///     .build();
+/// assert_eq!(qs, "?a=1&b=x%20y");
+/// ```
+#[derive(Default)]
+pub struct QueryStringBuilder {
+    pairs: Vec<(String, String)>,
+}
+
+impl QueryStringBuilder {
+    /// Returns new empty QueryStringBuilder.
+    pub fn new() -> QueryStringBuilder {
+        QueryStringBuilder { pairs: vec![] }
+    }
+
+    /// Appends a key-value pair; both sides are encoded on build.
+    pub fn append(mut self, key: &str, value: &str) -> QueryStringBuilder {
+        self.pairs.push((key.to_owned(), value.to_owned()));
+        self
+    }
+
+    /// Builds the query string including the leading `?`, or an empty
+    /// string when no pairs were appended.
+    pub fn build(&self) -> String {
+        if self.pairs.is_empty() {
+            return String::new();
+        }
+
+        let mut out = String::from("?");
+        for (i, (key, value)) in self.pairs.iter().enumerate() {
+            if i > 0 {
+                out.push('&');
+            }
+            out.push_str(&encode_query_component(key));
+            out.push('=');
+            out.push_str(&encode_query_component(value));
+        }
+        out
+    }
+}
+
+fn is_unreserved(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~')
+}
+
+fn encode(s: &str, keep: impl Fn(u8) -> bool) -> String {
+    let mut out = String::with_capacity(s.len());
+    for &b in s.as_bytes() {
+        if keep(b) {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+fn decode_bytes(s: &str) -> Option<Vec<u8>> {
+    let bytes = s.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            out.push(unescape_at(bytes, i)?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    Some(out)
+}
+
+/// Decodes the `%XX` escape starting at `i`, if there is a valid one.
+fn unescape_at(bytes: &[u8], i: usize) -> Option<u8> {
+    if bytes[i] != b'%' {
+        return None;
+    }
+    let hex = bytes.get(i + 1..i + 3)?;
+    u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    const SAMPLES: [&str; 6] = [
+        "plain",
+        "with space",
+        "unicode: käse 🧀",
+        "reserved &=?#/%+",
+        "path/with/slashes",
+        "",
+    ];
+
+    #[test]
+    fn decode_encode_path_segment_round_trips() {
+        for s in SAMPLES {
+            assert_eq!(decode(&encode_path_segment(s)).as_deref(), Some(s));
+        }
+    }
+
+    #[test]
+    fn decode_encode_query_component_round_trips() {
+        for s in SAMPLES {
+            assert_eq!(decode(&encode_query_component(s)).as_deref(), Some(s));
+        }
+    }
+
+    #[test]
+    fn path_segment_encodes_slash() {
+        assert!(!encode_path_segment("a/b").contains('/'));
+        assert!(encode_query_component("a/b").contains('/'));
+    }
+
+    #[test]
+    fn strict_decode_rejects_invalid_escapes() {
+        assert_eq!(decode("%zz"), None);
+        assert_eq!(decode("%2"), None);
+        assert_eq!(decode("%ff"), None); // invalid UTF-8
+    }
+
+    #[test]
+    fn lossy_decode_keeps_invalid_escapes() {
+        assert_eq!(decode_lossy("100%"), "100%");
+        assert_eq!(decode_lossy("a%2zb"), "a%2zb");
+        assert_eq!(decode_lossy("a%20b"), "a b");
+    }
+
+    #[test]
+    fn query_string_builder() {
+        assert_eq!(QueryStringBuilder::new().build(), "");
+        assert_eq!(
+            QueryStringBuilder::new()
+                .append("a", "1")
+                .append("b", "x y")
+                .append("c&d", "e=f")
+                .build(),
+            "?a=1&b=x%20y&c%26d=e%3Df"
+        );
+    }
+}